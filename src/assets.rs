@@ -0,0 +1,588 @@
+use mime_guess;
+use url::Url;
+
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+use super::percent;
+use super::{Diagnostic, DiagnosticKind, Error, Icon, Manifest, Size};
+
+impl<'a> Manifest<'a> {
+  /// Check the icon files against the values declared in the manifest.
+  ///
+  /// Every icon `src` is resolved into `dir`, the directory the manifest is
+  /// served from: `icons/a.png` and `/icons/a.png` both map to
+  /// `dir/icons/a.png`. Icons on another host are skipped. PNG, JPEG, WebP,
+  /// GIF, ICO and SVG files are recognized by their contents.
+  ///
+  /// Reported as errors: `src` values with encoded path segments that could
+  /// escape `dir`, such as `%2E%2E` or `%2F`, missing files, unrecognized
+  /// files, dimensions that don't match `sizes`, and contents that don't match
+  /// `type` (or the type implied by the file extension). Icons that are not
+  /// square are reported as warnings.
+  ///
+  /// ## Errors
+  /// Fails if a file exists but can't be read.
+  ///
+  /// ## Example
+  /// ```rust,no_run
+  /// # extern crate webmanifest;
+  /// # use webmanifest::{Icon, Manifest};
+  /// # fn main() -> Result<(), webmanifest::Error> {
+  /// let manifest = Manifest::builder("My Cool Application")
+  ///   .icon(Icon::new("images/touch/homescreen192.png", (192, 192)));
+  /// for diagnostic in manifest.check_assets("public")? {
+  ///   println!("{}", diagnostic);
+  /// }
+  /// # Ok(())}
+  /// ```
+  pub fn check_assets(
+    &self,
+    dir: impl AsRef<Path>,
+  ) -> Result<Vec<Diagnostic>, Error> {
+    let mut diagnostics = vec![];
+    for (index, icon) in self.icons.iter().enumerate() {
+      let member = format!("icons[{}]", index);
+      check_icon(icon, dir.as_ref(), &member, &mut diagnostics)?;
+    }
+    Ok(diagnostics)
+  }
+}
+
+/// Check a single icon file.
+fn check_icon(
+  icon: &Icon,
+  dir: &Path,
+  member: &str,
+  diagnostics: &mut Vec<Diagnostic>,
+) -> Result<(), Error> {
+  let src_member = format!("{}.src", member);
+  let path = match local_path(dir, icon.get_src()) {
+    Ok(Some(path)) => path,
+    Ok(None) => return Ok(()),
+    Err(kind) => {
+      diagnostics.push(Diagnostic::error(src_member, kind));
+      return Ok(());
+    }
+  };
+  let display = path.display().to_string();
+
+  let bytes = match fs::read(&path) {
+    Ok(bytes) => bytes,
+    Err(ref err) if err.kind() == io::ErrorKind::NotFound => {
+      let kind = DiagnosticKind::MissingFile { path: display };
+      diagnostics.push(Diagnostic::error(src_member, kind));
+      return Ok(());
+    }
+    Err(err) => return Err(Error::Io(err)),
+  };
+
+  let image = match ImageInfo::sniff(&bytes) {
+    Some(image) => image,
+    None => {
+      let kind = DiagnosticKind::UnknownImageFormat { path: display };
+      diagnostics.push(Diagnostic::error(src_member, kind));
+      return Ok(());
+    }
+  };
+
+  let declared = match icon.get_icon_type() {
+    Some(declared) => declared.to_string(),
+    None => mime_guess::from_path(&path)
+      .first_or_octet_stream()
+      .to_string(),
+  };
+  if normalize_mime(&declared) != image.mime {
+    let kind = DiagnosticKind::TypeMismatch {
+      declared,
+      actual: image.mime.to_string(),
+    };
+    diagnostics.push(Diagnostic::error(format!("{}.type", member), kind));
+  }
+
+  if let Some(sizes) = icon.get_sizes() {
+    let declared: Vec<_> = sizes
+      .iter()
+      .filter_map(|size| match *size {
+        Size::Exact { width, height } => Some((width, height)),
+        Size::Any => None,
+      })
+      .collect();
+    let matches = declared.is_empty()
+      || image.sizes.is_empty()
+      || declared.iter().all(|size| image.sizes.contains(size));
+    if !matches {
+      let kind = DiagnosticKind::SizeMismatch {
+        declared: sizes.to_string(),
+        actual: format_sizes(&image.sizes),
+      };
+      diagnostics.push(Diagnostic::error(format!("{}.sizes", member), kind));
+    }
+  }
+
+  for &(width, height) in &image.sizes {
+    if width != height {
+      let kind = DiagnosticKind::NotSquare { width, height };
+      diagnostics.push(Diagnostic::warning(src_member.clone(), kind));
+    }
+  }
+
+  Ok(())
+}
+
+/// Map an icon `src` to a path inside `dir`. Returns `None` for URLs on
+/// another host, and fails with `DiagnosticKind::InvalidPath` if a decoded
+/// path segment is not a plain file name, e.g. `%2E%2E` or `%2Fetc`.
+pub(crate) fn local_path(
+  dir: &Path,
+  src: &str,
+) -> Result<Option<PathBuf>, DiagnosticKind> {
+  let base = Url::parse("http://localhost/").ok();
+  let url = match base.as_ref().and_then(|base| base.join(src).ok()) {
+    Some(url) => url,
+    None => return Ok(None),
+  };
+  if Some(url.origin()) != base.map(|base| base.origin()) {
+    return Ok(None);
+  }
+  let mut path = dir.to_path_buf();
+  for segment in url.path_segments().into_iter().flatten() {
+    let segment =
+      String::from_utf8_lossy(&percent::decode(segment)).into_owned();
+    if segment.is_empty() {
+      continue;
+    }
+    if !is_file_name(&segment) {
+      return Err(DiagnosticKind::InvalidPath {
+        value: src.to_string(),
+      });
+    }
+    path.push(segment);
+  }
+  Ok(Some(path))
+}
+
+/// Check that a decoded path segment names a single entry of a directory:
+/// not `.` or `..`, not absolute, and without separators or NUL bytes.
+fn is_file_name(segment: &str) -> bool {
+  if segment.contains(&['/', '\\', '\0'][..]) {
+    return false;
+  }
+  let mut components = Path::new(segment).components();
+  matches!(
+    (components.next(), components.next()),
+    (Some(Component::Normal(_)), None)
+  )
+}
+
+/// Map MIME type aliases to the name used by `ImageInfo`.
+fn normalize_mime(mime: &str) -> String {
+  let mime = mime.trim().to_ascii_lowercase();
+  match mime.as_str() {
+    "image/jpg" | "image/pjpeg" => "image/jpeg".to_string(),
+    "image/vnd.microsoft.icon" | "image/ico" => "image/x-icon".to_string(),
+    _ => mime,
+  }
+}
+
+/// Format sizes the way the `sizes` member does.
+fn format_sizes(sizes: &[(u32, u32)]) -> String {
+  if sizes.is_empty() {
+    return "any".to_string();
+  }
+  let sizes: Vec<_> = sizes
+    .iter()
+    .map(|(width, height)| format!("{}x{}", width, height))
+    .collect();
+  sizes.join(" ")
+}
+
+/// The format and dimensions of an image, read from its header.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ImageInfo {
+  /// The MIME type matching the contents.
+  pub(crate) mime: &'static str,
+  /// The dimensions of every image in the file. ICO files can hold several;
+  /// SVG files without a fixed size hold none.
+  pub(crate) sizes: Vec<(u32, u32)>,
+}
+
+impl ImageInfo {
+  /// Recognize an image from its contents.
+  pub(crate) fn sniff(bytes: &[u8]) -> Option<Self> {
+    let (mime, sizes) = if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+      ("image/png", vec![png(bytes)?])
+    } else if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+      ("image/gif", vec![gif(bytes)?])
+    } else if bytes.starts_with(b"\xff\xd8") {
+      ("image/jpeg", vec![jpeg(bytes)?])
+    } else if bytes.starts_with(b"RIFF") && bytes.get(8..12) == Some(b"WEBP") {
+      ("image/webp", vec![webp(bytes)?])
+    } else if bytes.starts_with(b"\x00\x00\x01\x00") {
+      ("image/x-icon", ico(bytes)?)
+    } else {
+      ("image/svg+xml", svg(bytes)?)
+    };
+    Some(Self { mime, sizes })
+  }
+}
+
+fn be16(bytes: &[u8], at: usize) -> Option<u32> {
+  let b = bytes.get(at..at + 2)?;
+  Some(u32::from(b[0]) << 8 | u32::from(b[1]))
+}
+
+fn be32(bytes: &[u8], at: usize) -> Option<u32> {
+  Some(be16(bytes, at)? << 16 | be16(bytes, at + 2)?)
+}
+
+fn le16(bytes: &[u8], at: usize) -> Option<u32> {
+  let b = bytes.get(at..at + 2)?;
+  Some(u32::from(b[0]) | u32::from(b[1]) << 8)
+}
+
+fn le24(bytes: &[u8], at: usize) -> Option<u32> {
+  Some(le16(bytes, at)? | u32::from(*bytes.get(at + 2)?) << 16)
+}
+
+/// Read the `IHDR` chunk.
+fn png(bytes: &[u8]) -> Option<(u32, u32)> {
+  if bytes.get(12..16)? != b"IHDR" {
+    return None;
+  }
+  Some((be32(bytes, 16)?, be32(bytes, 20)?))
+}
+
+/// Read the logical screen descriptor.
+fn gif(bytes: &[u8]) -> Option<(u32, u32)> {
+  Some((le16(bytes, 6)?, le16(bytes, 8)?))
+}
+
+/// Walk the segments up to the first start-of-frame marker.
+fn jpeg(bytes: &[u8]) -> Option<(u32, u32)> {
+  let mut at = 2;
+  loop {
+    while *bytes.get(at)? != 0xff {
+      at += 1;
+    }
+    while *bytes.get(at)? == 0xff {
+      at += 1;
+    }
+    let marker = *bytes.get(at)?;
+    at += 1;
+    match marker {
+      // Markers without a payload.
+      0x01 | 0xd0..=0xd7 => continue,
+      // Start of frame, excluding DHT, JPG and DAC.
+      0xc0..=0xcf if marker != 0xc4 && marker != 0xc8 && marker != 0xcc => {
+        return Some((be16(bytes, at + 5)?, be16(bytes, at + 3)?));
+      }
+      _ => at += be16(bytes, at)? as usize,
+    }
+  }
+}
+
+/// Read the first chunk of a lossy, lossless or extended WebP file.
+fn webp(bytes: &[u8]) -> Option<(u32, u32)> {
+  match bytes.get(12..16)? {
+    b"VP8 " => Some((le16(bytes, 26)? & 0x3fff, le16(bytes, 28)? & 0x3fff)),
+    b"VP8L" => {
+      let b = bytes.get(21..25)?;
+      let (b0, b1, b2, b3) = (
+        u32::from(b[0]),
+        u32::from(b[1]),
+        u32::from(b[2]),
+        u32::from(b[3]),
+      );
+      let width = 1 + (b0 | (b1 & 0x3f) << 8);
+      let height = 1 + (b1 >> 6 | b2 << 2 | (b3 & 0x0f) << 10);
+      Some((width, height))
+    }
+    b"VP8X" => Some((1 + le24(bytes, 24)?, 1 + le24(bytes, 27)?)),
+    _ => None,
+  }
+}
+
+/// Read every entry of the icon directory.
+fn ico(bytes: &[u8]) -> Option<Vec<(u32, u32)>> {
+  let count = le16(bytes, 4)? as usize;
+  let mut sizes = Vec::with_capacity(count);
+  for index in 0..count {
+    let entry = bytes.get(6 + index * 16..6 + index * 16 + 2)?;
+    // A stored dimension of 0 means 256 pixels.
+    let dimension = |b: u8| if b == 0 { 256 } else { u32::from(b) };
+    let size = (dimension(entry[0]), dimension(entry[1]));
+    if !sizes.contains(&size) {
+      sizes.push(size);
+    }
+  }
+  Some(sizes)
+}
+
+/// Read the `width` and `height` attributes of the root `<svg>` element. Both
+/// must be unitless or in `px` to count as a fixed size.
+fn svg(bytes: &[u8]) -> Option<Vec<(u32, u32)>> {
+  let text = ::std::str::from_utf8(bytes).ok()?;
+  let start = text.find("<svg")?;
+  let end = start + text[start..].find('>')?;
+  let tag = &text[start + 4..end];
+  let width = attribute(tag, "width").and_then(pixels);
+  let height = attribute(tag, "height").and_then(pixels);
+  match (width, height) {
+    (Some(width), Some(height)) => Some(vec![(width, height)]),
+    _ => Some(vec![]),
+  }
+}
+
+/// Find the value of an attribute in the inside of a tag.
+fn attribute<'t>(mut tag: &'t str, name: &str) -> Option<&'t str> {
+  loop {
+    tag = tag.trim_start();
+    let eq = tag.find('=')?;
+    let key = tag[..eq].trim();
+    let rest = tag[eq + 1..].trim_start();
+    let quote = rest.chars().next()?;
+    if quote != '"' && quote != '\'' {
+      return None;
+    }
+    let close = 1 + rest[1..].find(quote)?;
+    if key == name {
+      return Some(&rest[1..close]);
+    }
+    tag = &rest[close + 1..];
+  }
+}
+
+/// Parse a unitless or `px` length as whole pixels.
+fn pixels(value: &str) -> Option<u32> {
+  let value = value.trim();
+  let value = value.trim_end_matches("px");
+  let pixels: f64 = value.parse().ok()?;
+  if pixels > 0.0 && pixels.fract() == 0.0 && pixels <= f64::from(u32::MAX) {
+    Some(pixels as u32)
+  } else {
+    None
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn invalid(src: &str) -> Result<Option<PathBuf>, DiagnosticKind> {
+    Err(DiagnosticKind::InvalidPath {
+      value: src.to_string(),
+    })
+  }
+
+  #[test]
+  fn local_path_maps_into_dir() {
+    let dir = Path::new("/srv/public");
+    let path = local_path(dir, "/icons/a%20b.png");
+    assert_eq!(path, Ok(Some(dir.join("icons").join("a b.png"))));
+    let path = local_path(dir, "icons/a.png");
+    assert_eq!(path, Ok(Some(dir.join("icons").join("a.png"))));
+    assert_eq!(local_path(dir, "https://example.com/a.png"), Ok(None));
+  }
+
+  #[test]
+  fn local_path_normalizes_dot_segments() {
+    let dir = Path::new("/srv/public");
+    let path = local_path(dir, "/../%2e%2E/./%2E/a.png");
+    assert_eq!(path, Ok(Some(dir.join("a.png"))));
+  }
+
+  #[test]
+  fn local_path_rejects_encoded_slash() {
+    let dir = Path::new("/srv/public");
+    let src = "/%2Fetc%2Fpasswd";
+    assert_eq!(local_path(dir, src), invalid(src));
+    let src = "/..%2F..%2Fetc%2Fhostname";
+    assert_eq!(local_path(dir, src), invalid(src));
+  }
+
+  #[test]
+  fn local_path_rejects_encoded_backslash() {
+    let dir = Path::new("/srv/public");
+    let src = "/..%5C..%5Cetc%5Chostname";
+    assert_eq!(local_path(dir, src), invalid(src));
+  }
+
+  #[test]
+  fn local_path_rejects_nul() {
+    let dir = Path::new("/srv/public");
+    let src = "/icon.png%00.txt";
+    assert_eq!(local_path(dir, src), invalid(src));
+  }
+
+  #[test]
+  fn file_name_rejects_dot_segments() {
+    assert!(!is_file_name("."));
+    assert!(!is_file_name(".."));
+    assert!(is_file_name("..a"));
+    assert!(is_file_name(".well-known"));
+  }
+
+  #[test]
+  fn file_name_rejects_absolute_paths() {
+    assert!(!is_file_name("/etc"));
+    assert!(!is_file_name("\\etc"));
+    assert!(is_file_name("etc"));
+  }
+
+  #[test]
+  fn check_assets_reports_invalid_path() {
+    let src = "/%2Fetc%2Fpasswd";
+    let manifest = Manifest::builder("App").icon(Icon::new(src, (1, 1)));
+    let diagnostics = manifest.check_assets("/srv/public").unwrap();
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].get_member(), "icons[0].src");
+    assert_eq!(diagnostics[0].get_kind(), &invalid(src).unwrap_err());
+  }
+
+  fn sizes(bytes: &[u8]) -> Option<(&'static str, Vec<(u32, u32)>)> {
+    ImageInfo::sniff(bytes).map(|image| (image.mime, image.sizes))
+  }
+
+  /// Check that every prefix of a valid header is rejected instead of
+  /// reading out of bounds.
+  fn assert_truncations_rejected(bytes: &[u8]) {
+    for len in 0..bytes.len() {
+      assert_eq!(ImageInfo::sniff(&bytes[..len]), None, "length {}", len);
+    }
+  }
+
+  fn png_fixture() -> Vec<u8> {
+    let mut bytes = b"\x89PNG\r\n\x1a\n\x00\x00\x00\x0dIHDR".to_vec();
+    bytes.extend_from_slice(&[0, 0, 1, 0, 0, 0, 0, 192]);
+    bytes
+  }
+
+  #[test]
+  fn sniff_png() {
+    let bytes = png_fixture();
+    assert_eq!(sizes(&bytes), Some(("image/png", vec![(256, 192)])));
+    assert_truncations_rejected(&bytes);
+  }
+
+  #[test]
+  fn sniff_png_without_ihdr() {
+    let mut bytes = png_fixture();
+    bytes[12..16].copy_from_slice(b"IDAT");
+    assert_eq!(sizes(&bytes), None);
+  }
+
+  #[test]
+  fn sniff_gif() {
+    for signature in &[b"GIF87a", b"GIF89a"] {
+      let mut bytes = signature.to_vec();
+      bytes.extend_from_slice(&[0x2c, 0x01, 0x10, 0x00]);
+      assert_eq!(sizes(&bytes), Some(("image/gif", vec![(300, 16)])));
+      assert_truncations_rejected(&bytes);
+    }
+  }
+
+  fn jpeg_fixture() -> Vec<u8> {
+    let mut bytes = vec![0xff, 0xd8];
+    // APP0 (JFIF) and APP1 segments come before the frame header.
+    bytes.extend_from_slice(&[0xff, 0xe0, 0x00, 0x10]);
+    bytes.extend_from_slice(b"JFIF\x00\x01\x01\x00\x00\x01\x00\x01\x00\x00");
+    bytes.extend_from_slice(&[0xff, 0xe1, 0x00, 0x08]);
+    bytes.extend_from_slice(b"Exif\x00\x00");
+    // A DHT segment, whose marker lies in the SOF range.
+    bytes.extend_from_slice(&[0xff, 0xc4, 0x00, 0x03, 0x00]);
+    // Fill bytes before the SOF2 marker.
+    bytes.extend_from_slice(&[0xff, 0xff, 0xc2, 0x00, 0x11, 0x08]);
+    bytes.extend_from_slice(&[0x01, 0xe0, 0x02, 0x80]);
+    bytes
+  }
+
+  #[test]
+  fn sniff_jpeg_after_app_segments() {
+    let bytes = jpeg_fixture();
+    assert_eq!(sizes(&bytes), Some(("image/jpeg", vec![(640, 480)])));
+    assert_truncations_rejected(&bytes);
+  }
+
+  #[test]
+  fn sniff_jpeg_without_frame() {
+    let bytes = [0xff, 0xd8, 0xff, 0xd9];
+    assert_eq!(sizes(&bytes), None);
+  }
+
+  fn webp_fixture(chunk: &[u8], payload: &[u8]) -> Vec<u8> {
+    let mut bytes = b"RIFF\x00\x00\x00\x00WEBP".to_vec();
+    bytes.extend_from_slice(chunk);
+    bytes.extend_from_slice(&[0, 0, 0, 0]);
+    bytes.extend_from_slice(payload);
+    bytes
+  }
+
+  #[test]
+  fn sniff_webp_lossy() {
+    let payload = [0, 0, 0, 0x9d, 0x01, 0x2a, 0x90, 0x01, 0xc8, 0x00];
+    let bytes = webp_fixture(b"VP8 ", &payload);
+    assert_eq!(sizes(&bytes), Some(("image/webp", vec![(400, 200)])));
+    assert_truncations_rejected(&bytes);
+  }
+
+  #[test]
+  fn sniff_webp_lossless() {
+    // 400x200: 14 bits of width - 1, then 14 bits of height - 1.
+    let bits: u32 = 399 | 199 << 14;
+    let mut payload = vec![0x2f];
+    payload.extend_from_slice(&bits.to_le_bytes());
+    let bytes = webp_fixture(b"VP8L", &payload);
+    assert_eq!(sizes(&bytes), Some(("image/webp", vec![(400, 200)])));
+    assert_truncations_rejected(&bytes);
+  }
+
+  #[test]
+  fn sniff_webp_extended() {
+    let payload = [0x10, 0, 0, 0, 0x8f, 0x01, 0x00, 0xc7, 0x00, 0x00];
+    let bytes = webp_fixture(b"VP8X", &payload);
+    assert_eq!(sizes(&bytes), Some(("image/webp", vec![(400, 200)])));
+    assert_truncations_rejected(&bytes);
+  }
+
+  #[test]
+  fn sniff_webp_unknown_chunk() {
+    let bytes = webp_fixture(b"ALPH", &[0; 10]);
+    assert_eq!(sizes(&bytes), None);
+  }
+
+  #[test]
+  fn sniff_ico_with_256_entry() {
+    let mut bytes = vec![0, 0, 1, 0, 3, 0];
+    for &(width, height) in &[(16, 16), (0, 0), (16, 16)] {
+      let mut entry = [0; 16];
+      entry[0] = width;
+      entry[1] = height;
+      bytes.extend_from_slice(&entry);
+    }
+    let expected = vec![(16, 16), (256, 256)];
+    assert_eq!(sizes(&bytes), Some(("image/x-icon", expected)));
+  }
+
+  #[test]
+  fn sniff_ico_truncated_directory() {
+    let mut bytes = vec![0, 0, 1, 0, 2, 0];
+    bytes.extend_from_slice(&[32; 16]);
+    bytes.push(48);
+    assert_eq!(sizes(&bytes), None);
+  }
+
+  #[test]
+  fn sniff_svg() {
+    let svg = br#"<?xml version="1.0"?>
+      <svg xmlns="http://www.w3.org/2000/svg" width="48px" height='32'>"#;
+    assert_eq!(sizes(svg), Some(("image/svg+xml", vec![(48, 32)])));
+    let svg = br#"<svg viewBox="0 0 48 48" width="100%">"#;
+    assert_eq!(sizes(svg), Some(("image/svg+xml", vec![])));
+    assert_eq!(sizes(b"<svg width=\"48\""), None);
+    assert_eq!(sizes(b"not an image"), None);
+    assert_eq!(sizes(b""), None);
+    assert_eq!(sizes(b"\xff\xfe<svg>"), None);
+  }
+}
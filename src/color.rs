@@ -0,0 +1,915 @@
+use std::borrow::Cow;
+use std::fmt;
+use std::str::FromStr;
+
+use super::{Diagnostic, DiagnosticKind, Error};
+
+/// A CSS color, the value of `theme_color` and `background_color`.
+///
+/// Parses hex colors with 3, 4, 6 or 8 digits, named colors, `transparent`,
+/// `rgb()`, `rgba()`, `hsl()`, `hsla()` and `hwb()`, and the `lab()`, `lch()`,
+/// `oklab()`, `oklch()` and `color()` functions. Function names, keywords and
+/// hex digits are matched case-insensitively. `currentcolor` and system
+/// colors depend on a document, so they are rejected.
+///
+/// The color is stored in sRGB. Colors outside of the sRGB gamut keep their
+/// extended channel values, and are clipped per channel when converted to
+/// 8 bits, e.g. by `Color::to_hex`.
+///
+/// ## Example
+/// ```rust
+/// # extern crate webmanifest;
+/// # use webmanifest::Color;
+/// # fn main() -> Result<(), webmanifest::Error> {
+/// let color: Color = "rgb(255 0 0 / 50%)".parse()?;
+/// assert_eq!(color.to_hex(), "#ff000080");
+/// assert_eq!(Color::parse("RebeccaPurple")?.to_hex(), "#663399");
+/// assert_eq!(Color::parse("oklch(62.8% 0.2577 29.23)")?.to_hex(), "#ff0000");
+/// assert!(Color::parse("#00000").is_err());
+/// assert!(Color::parse("blak").is_err());
+/// # Ok(())}
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Color {
+  red: f64,
+  green: f64,
+  blue: f64,
+  alpha: f64,
+}
+
+impl Color {
+  /// Create an opaque color from 8 bit sRGB channels.
+  #[must_use]
+  #[inline]
+  pub fn rgb(red: u8, green: u8, blue: u8) -> Self {
+    Self::rgba(red, green, blue, 255)
+  }
+
+  /// Create a color from 8 bit sRGB channels and an 8 bit alpha channel.
+  #[must_use]
+  #[inline]
+  pub fn rgba(red: u8, green: u8, blue: u8, alpha: u8) -> Self {
+    Self {
+      red: f64::from(red) / 255.0,
+      green: f64::from(green) / 255.0,
+      blue: f64::from(blue) / 255.0,
+      alpha: f64::from(alpha) / 255.0,
+    }
+  }
+
+  /// Parse a CSS color, failing if `value` is not one.
+  pub fn parse(value: &str) -> Result<Self, Error> {
+    Self::parse_kind(value)
+      .map_err(|kind| Error::InvalidValue(Diagnostic::error("color", kind)))
+  }
+
+  /// Parse a CSS color, failing with the kind of diagnostic to report on the
+  /// member it came from.
+  pub(crate) fn parse_kind(value: &str) -> Result<Self, DiagnosticKind> {
+    parse_color(value).ok_or_else(|| DiagnosticKind::InvalidColor {
+      value: value.to_string(),
+    })
+  }
+
+  /// Get the red, green, blue and alpha channels as 8 bit values. Channels
+  /// outside of the sRGB gamut are clipped.
+  #[must_use]
+  pub fn to_rgba8(self) -> [u8; 4] {
+    let channel = |value: f64| (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+    [
+      channel(self.red),
+      channel(self.green),
+      channel(self.blue),
+      channel(self.alpha),
+    ]
+  }
+
+  /// Get the alpha channel, from 0 for transparent to 1 for opaque.
+  #[must_use]
+  #[inline]
+  pub fn get_alpha(self) -> f64 {
+    self.alpha
+  }
+
+  /// Get the relative luminance as defined by WCAG 2, from 0 for black to 1
+  /// for white. Channels outside of the sRGB gamut are clipped, and the alpha
+  /// channel is ignored.
+  #[must_use]
+  pub fn relative_luminance(self) -> f64 {
+    let [red, green, blue] = [self.red, self.green, self.blue]
+      .map(|channel| srgb_decode(channel.clamp(0.0, 1.0)));
+    0.2126 * red + 0.7152 * green + 0.0722 * blue
+  }
+
+  /// Get the WCAG 2 contrast ratio with `other`, from 1 for equal colors to
+  /// 21 for black on white. The alpha channels are ignored.
+  ///
+  /// ## Example
+  /// ```rust
+  /// # extern crate webmanifest;
+  /// # use webmanifest::Color;
+  /// let ratio = Color::rgb(0x21, 0x96, 0xf3).contrast_ratio(Color::rgb(255, 255, 255));
+  /// assert_eq!(format!("{:.2}", ratio), "3.12");
+  /// ```
+  #[must_use]
+  pub fn contrast_ratio(self, other: Color) -> f64 {
+    let (a, b) = (self.relative_luminance(), other.relative_luminance());
+    (a.max(b) + 0.05) / (a.min(b) + 0.05)
+  }
+
+  /// Check if the color is inside of the sRGB gamut, so converting it to
+  /// 8 bits does not clip it.
+  #[must_use]
+  pub fn is_in_srgb_gamut(self) -> bool {
+    // Allow for rounding errors of the conversion from other color spaces.
+    let range = -0.0001..=1.0001;
+    [self.red, self.green, self.blue]
+      .iter()
+      .all(|channel| range.contains(channel))
+  }
+
+  /// Convert to the canonical hex notation: lowercase `#rrggbb` for opaque
+  /// colors, and `#rrggbbaa` otherwise.
+  #[must_use]
+  pub fn to_hex(self) -> String {
+    let [red, green, blue, alpha] = self.to_rgba8();
+    if alpha == 255 {
+      format!("#{:02x}{:02x}{:02x}", red, green, blue)
+    } else {
+      format!("#{:02x}{:02x}{:02x}{:02x}", red, green, blue, alpha)
+    }
+  }
+}
+
+impl FromStr for Color {
+  type Err = Error;
+
+  #[inline]
+  fn from_str(value: &str) -> Result<Self, Self::Err> {
+    Self::parse(value)
+  }
+}
+
+impl fmt::Display for Color {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    f.write_str(&self.to_hex())
+  }
+}
+
+impl<'a> From<Color> for Cow<'a, str> {
+  #[inline]
+  fn from(color: Color) -> Self {
+    Cow::Owned(color.to_hex())
+  }
+}
+
+/// A component of a color function.
+#[derive(Clone, Copy)]
+enum Component {
+  Number(f64),
+  Percentage(f64),
+  /// An angle, in degrees.
+  Angle(f64),
+  /// The `none` keyword of the modern syntax.
+  Missing,
+}
+
+impl Component {
+  /// Parse a number, percentage, angle or `none`.
+  fn parse(token: &str) -> Option<Self> {
+    if token == "none" {
+      return Some(Component::Missing);
+    }
+    if let Some(value) = token.strip_suffix('%') {
+      return number(value).map(Component::Percentage);
+    }
+    let units = [
+      ("deg", 1.0),
+      ("grad", 0.9),
+      ("rad", 180.0 / std::f64::consts::PI),
+      ("turn", 360.0),
+    ];
+    for (unit, degrees) in units.iter() {
+      if let Some(value) = token.strip_suffix(unit) {
+        return number(value).map(|value| Component::Angle(value * degrees));
+      }
+    }
+    number(token).map(Component::Number)
+  }
+
+  /// Resolve a number or percentage, where 100% is `full`.
+  fn scaled(self, full: f64) -> Option<f64> {
+    match self {
+      Component::Number(value) => Some(value),
+      Component::Percentage(value) => Some(value / 100.0 * full),
+      Component::Angle(_) => None,
+      Component::Missing => Some(0.0),
+    }
+  }
+
+  /// Resolve a hue: a number of degrees or an angle.
+  fn hue(self) -> Option<f64> {
+    match self {
+      Component::Number(value) | Component::Angle(value) => {
+        Some(value.rem_euclid(360.0))
+      }
+      Component::Percentage(_) => None,
+      Component::Missing => Some(0.0),
+    }
+  }
+
+  /// Resolve an alpha value, clamped to `0..=1`.
+  fn alpha(self) -> Option<f64> {
+    self.scaled(1.0).map(|alpha| alpha.clamp(0.0, 1.0))
+  }
+}
+
+/// Parse a CSS number. Unlike `f64::from_str`, this rejects `inf` and `NaN`.
+fn number(value: &str) -> Option<f64> {
+  let valid = value.bytes().any(|b| b.is_ascii_digit())
+    && value
+      .bytes()
+      .all(|b| b.is_ascii_digit() || b"+-.e".contains(&b));
+  if !valid {
+    return None;
+  }
+  value.parse().ok()
+}
+
+/// Parse a color, returning `None` if it is invalid.
+fn parse_color(value: &str) -> Option<Color> {
+  let value = value.trim().to_ascii_lowercase();
+  if let Some(hex) = value.strip_prefix('#') {
+    return parse_hex(hex);
+  }
+  if let Some(open) = value.find('(') {
+    let args = value[open + 1..].strip_suffix(')')?;
+    return parse_function(&value[..open], args);
+  }
+  if value == "transparent" {
+    return Some(Color::rgba(0, 0, 0, 0));
+  }
+  let index = NAMED
+    .binary_search_by_key(&value.as_str(), |&(name, _)| name)
+    .ok()?;
+  let [_, red, green, blue] = NAMED[index].1.to_be_bytes();
+  Some(Color::rgb(red, green, blue))
+}
+
+/// Parse the digits of a hex color.
+fn parse_hex(hex: &str) -> Option<Color> {
+  if !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+    return None;
+  }
+  let channel = |digits: &str| u8::from_str_radix(digits, 16).ok();
+  let channels: Vec<u8> = match hex.len() {
+    3 | 4 => (0..hex.len())
+      .map(|index| channel(&hex[index..=index]).map(|digit| digit * 17))
+      .collect::<Option<_>>()?,
+    6 | 8 => (0..hex.len())
+      .step_by(2)
+      .map(|index| channel(&hex[index..index + 2]))
+      .collect::<Option<_>>()?,
+    _ => return None,
+  };
+  let alpha = channels.get(3).cloned().unwrap_or(255);
+  Some(Color::rgba(channels[0], channels[1], channels[2], alpha))
+}
+
+/// Parse a color function, given its name and the text between the
+/// parentheses.
+fn parse_function(name: &str, args: &str) -> Option<Color> {
+  if args.contains(',') {
+    return match name {
+      "rgb" | "rgba" => parse_legacy_rgb(args),
+      "hsl" | "hsla" => parse_legacy_hsl(args),
+      _ => None,
+    };
+  }
+
+  let mut parts = args.split('/');
+  let channels: Vec<&str> = parts.next()?.split_whitespace().collect();
+  let alpha = match parts.next() {
+    Some(alpha) => match alpha.split_whitespace().collect::<Vec<_>>()[..] {
+      [alpha] => Component::parse(alpha)?.alpha()?,
+      _ => return None,
+    },
+    None => 1.0,
+  };
+  if parts.next().is_some() {
+    return None;
+  }
+
+  let (space, channels) = match (name, &channels[..]) {
+    ("color", [space, rest @ ..]) => (Some(*space), rest),
+    ("color", []) => return None,
+    (_, channels) => (None, channels),
+  };
+  let [first, second, third] = match channels {
+    [first, second, third] => [
+      Component::parse(first)?,
+      Component::parse(second)?,
+      Component::parse(third)?,
+    ],
+    _ => return None,
+  };
+
+  let rgb = match name {
+    "rgb" | "rgba" => [
+      (first.scaled(255.0)? / 255.0).clamp(0.0, 1.0),
+      (second.scaled(255.0)? / 255.0).clamp(0.0, 1.0),
+      (third.scaled(255.0)? / 255.0).clamp(0.0, 1.0),
+    ],
+    "hsl" | "hsla" => hsl_to_srgb(
+      first.hue()?,
+      second.scaled(100.0)?.clamp(0.0, 100.0) / 100.0,
+      third.scaled(100.0)?.clamp(0.0, 100.0) / 100.0,
+    ),
+    "hwb" => hwb_to_srgb(
+      first.hue()?,
+      second.scaled(100.0)?.clamp(0.0, 100.0) / 100.0,
+      third.scaled(100.0)?.clamp(0.0, 100.0) / 100.0,
+    ),
+    "lab" => lab_to_srgb(
+      first.scaled(100.0)?.clamp(0.0, 100.0),
+      second.scaled(125.0)?,
+      third.scaled(125.0)?,
+    ),
+    "lch" => {
+      let (a, b) = polar(second.scaled(150.0)?, third.hue()?);
+      lab_to_srgb(first.scaled(100.0)?.clamp(0.0, 100.0), a, b)
+    }
+    "oklab" => oklab_to_srgb(
+      first.scaled(1.0)?.clamp(0.0, 1.0),
+      second.scaled(0.4)?,
+      third.scaled(0.4)?,
+    ),
+    "oklch" => {
+      let (a, b) = polar(second.scaled(0.4)?, third.hue()?);
+      oklab_to_srgb(first.scaled(1.0)?.clamp(0.0, 1.0), a, b)
+    }
+    "color" => {
+      let values =
+        [first.scaled(1.0)?, second.scaled(1.0)?, third.scaled(1.0)?];
+      predefined_to_srgb(space?, values)?
+    }
+    _ => return None,
+  };
+  let [red, green, blue] = rgb;
+  Some(Color {
+    red,
+    green,
+    blue,
+    alpha,
+  })
+}
+
+/// Parse the comma-separated arguments of `rgb()` or `rgba()`. The channels
+/// must be all numbers or all percentages.
+fn parse_legacy_rgb(args: &str) -> Option<Color> {
+  use self::Component::{Number, Percentage};
+
+  let (channels, alpha) = legacy_components(args)?;
+  let rgb = match channels {
+    [Number(red), Number(green), Number(blue)] => {
+      [red / 255.0, green / 255.0, blue / 255.0]
+    }
+    [Percentage(red), Percentage(green), Percentage(blue)] => {
+      [red / 100.0, green / 100.0, blue / 100.0]
+    }
+    _ => return None,
+  };
+  let [red, green, blue] = rgb.map(|channel| channel.clamp(0.0, 1.0));
+  Some(Color {
+    red,
+    green,
+    blue,
+    alpha,
+  })
+}
+
+/// Parse the comma-separated arguments of `hsl()` or `hsla()`. Saturation
+/// and lightness must be percentages.
+fn parse_legacy_hsl(args: &str) -> Option<Color> {
+  use self::Component::Percentage;
+
+  let (channels, alpha) = legacy_components(args)?;
+  let (hue, saturation, lightness) = match channels {
+    [hue, Percentage(saturation), Percentage(lightness)] => {
+      (hue.hue()?, saturation, lightness)
+    }
+    _ => return None,
+  };
+  let [red, green, blue] = hsl_to_srgb(
+    hue,
+    saturation.clamp(0.0, 100.0) / 100.0,
+    lightness.clamp(0.0, 100.0) / 100.0,
+  );
+  Some(Color {
+    red,
+    green,
+    blue,
+    alpha,
+  })
+}
+
+/// Split comma-separated arguments into three channels and an alpha value.
+/// `none` is not allowed.
+fn legacy_components(args: &str) -> Option<([Component; 3], f64)> {
+  let components = args
+    .split(',')
+    .map(|arg| match Component::parse(arg.trim())? {
+      Component::Missing => None,
+      component => Some(component),
+    })
+    .collect::<Option<Vec<_>>>()?;
+  match components[..] {
+    [first, second, third] => Some(([first, second, third], 1.0)),
+    [first, second, third, alpha] => {
+      Some(([first, second, third], alpha.alpha()?))
+    }
+    _ => None,
+  }
+}
+
+/// Convert HSL, with saturation and lightness from 0 to 1, to sRGB.
+fn hsl_to_srgb(hue: f64, saturation: f64, lightness: f64) -> [f64; 3] {
+  let channel = |n: f64| {
+    let k = (n + hue / 30.0) % 12.0;
+    let a = saturation * lightness.min(1.0 - lightness);
+    lightness - a * (k - 3.0).min(9.0 - k).clamp(-1.0, 1.0)
+  };
+  [channel(0.0), channel(8.0), channel(4.0)]
+}
+
+/// Convert HWB, with whiteness and blackness from 0 to 1, to sRGB.
+fn hwb_to_srgb(hue: f64, whiteness: f64, blackness: f64) -> [f64; 3] {
+  if whiteness + blackness >= 1.0 {
+    let gray = whiteness / (whiteness + blackness);
+    return [gray, gray, gray];
+  }
+  hsl_to_srgb(hue, 1.0, 0.5)
+    .map(|channel| channel * (1.0 - whiteness - blackness) + whiteness)
+}
+
+/// Convert the chroma and hue of a polar color to the `a` and `b` axes.
+fn polar(chroma: f64, hue: f64) -> (f64, f64) {
+  let chroma = chroma.max(0.0);
+  let hue = hue.to_radians();
+  (chroma * hue.cos(), chroma * hue.sin())
+}
+
+/// Convert CIE Lab, relative to a D50 white point, to sRGB.
+fn lab_to_srgb(lightness: f64, a: f64, b: f64) -> [f64; 3] {
+  const KAPPA: f64 = 24389.0 / 27.0;
+  const EPSILON: f64 = 216.0 / 24389.0;
+  let f1 = (lightness + 16.0) / 116.0;
+  let f0 = a / 500.0 + f1;
+  let f2 = f1 - b / 200.0;
+  let component = |f: f64| {
+    if f.powi(3) > EPSILON {
+      f.powi(3)
+    } else {
+      (116.0 * f - 16.0) / KAPPA
+    }
+  };
+  let y = if lightness > KAPPA * EPSILON {
+    f1.powi(3)
+  } else {
+    lightness / KAPPA
+  };
+  let xyz = [component(f0), y, component(f2)];
+  let xyz = [xyz[0] * D50[0], xyz[1] * D50[1], xyz[2] * D50[2]];
+  xyz_d65_to_srgb(multiply(&D50_TO_D65, xyz))
+}
+
+/// Convert OKLab to sRGB.
+fn oklab_to_srgb(lightness: f64, a: f64, b: f64) -> [f64; 3] {
+  let lms = multiply(&OKLAB_TO_LMS, [lightness, a, b]).map(|c| c.powi(3));
+  multiply(&LMS_TO_LINEAR_SRGB, lms).map(srgb_encode)
+}
+
+/// Convert the channels of a predefined color space of `color()` to sRGB.
+fn predefined_to_srgb(space: &str, values: [f64; 3]) -> Option<[f64; 3]> {
+  let rgb = match space {
+    "srgb" => values,
+    "srgb-linear" => values.map(srgb_encode),
+    "display-p3" => {
+      xyz_d65_to_srgb(multiply(&P3_TO_XYZ, values.map(srgb_decode)))
+    }
+    "a98-rgb" => {
+      let linear = values.map(|c| c.signum() * c.abs().powf(563.0 / 256.0));
+      xyz_d65_to_srgb(multiply(&A98_TO_XYZ, linear))
+    }
+    "prophoto-rgb" => {
+      let linear = values.map(|c| {
+        if c.abs() <= 16.0 / 512.0 {
+          c / 16.0
+        } else {
+          c.signum() * c.abs().powf(1.8)
+        }
+      });
+      let xyz = multiply(&PROPHOTO_TO_XYZ, linear);
+      xyz_d65_to_srgb(multiply(&D50_TO_D65, xyz))
+    }
+    "rec2020" => {
+      const ALPHA: f64 = 1.099_296_826_809_44;
+      const BETA: f64 = 0.018_053_968_510_807;
+      let linear = values.map(|c| {
+        if c.abs() < BETA * 4.5 {
+          c / 4.5
+        } else {
+          c.signum() * ((c.abs() + ALPHA - 1.0) / ALPHA).powf(1.0 / 0.45)
+        }
+      });
+      xyz_d65_to_srgb(multiply(&REC2020_TO_XYZ, linear))
+    }
+    "xyz" | "xyz-d65" => xyz_d65_to_srgb(values),
+    "xyz-d50" => xyz_d65_to_srgb(multiply(&D50_TO_D65, values)),
+    _ => return None,
+  };
+  Some(rgb)
+}
+
+/// Convert CIE XYZ, relative to a D65 white point, to sRGB.
+fn xyz_d65_to_srgb(xyz: [f64; 3]) -> [f64; 3] {
+  multiply(&XYZ_TO_LINEAR_SRGB, xyz).map(srgb_encode)
+}
+
+/// Apply the sRGB transfer function to a linear channel.
+fn srgb_encode(channel: f64) -> f64 {
+  if channel.abs() > 0.003_130_8 {
+    channel.signum() * (1.055 * channel.abs().powf(1.0 / 2.4) - 0.055)
+  } else {
+    12.92 * channel
+  }
+}
+
+/// Undo the sRGB transfer function.
+fn srgb_decode(channel: f64) -> f64 {
+  if channel.abs() <= 0.040_45 {
+    channel / 12.92
+  } else {
+    channel.signum() * ((channel.abs() + 0.055) / 1.055).powf(2.4)
+  }
+}
+
+/// Multiply a 3x3 matrix with a vector.
+fn multiply(matrix: &[[f64; 3]; 3], vector: [f64; 3]) -> [f64; 3] {
+  matrix.map(|row| row[0] * vector[0] + row[1] * vector[1] + row[2] * vector[2])
+}
+
+/// The D50 white point in XYZ.
+const D50: [f64; 3] = [0.3457 / 0.3585, 1.0, (1.0 - 0.3457 - 0.3585) / 0.3585];
+
+/// Bradford chromatic adaptation from D50 to D65.
+const D50_TO_D65: [[f64; 3]; 3] = [
+  [
+    0.955_473_421_488_075,
+    -0.023_098_454_948_764_71,
+    0.063_259_243_200_570_72,
+  ],
+  [
+    -0.028_369_709_333_863_7,
+    1.009_995_398_081_304_1,
+    0.021_041_441_191_917_32,
+  ],
+  [
+    0.012_314_014_864_481_998,
+    -0.020_507_649_298_898_964,
+    1.330_365_926_242_124,
+  ],
+];
+
+const XYZ_TO_LINEAR_SRGB: [[f64; 3]; 3] = [
+  [
+    3.240_969_941_904_522_6,
+    -1.537_383_177_570_094,
+    -0.498_610_760_293_003_4,
+  ],
+  [
+    -0.969_243_636_280_879_6,
+    1.875_967_501_507_720_2,
+    0.041_555_057_407_175_59,
+  ],
+  [
+    0.055_630_079_696_993_66,
+    -0.203_976_958_888_976_52,
+    1.056_971_514_242_878_6,
+  ],
+];
+
+const P3_TO_XYZ: [[f64; 3]; 3] = [
+  [
+    0.486_570_948_648_216_2,
+    0.265_667_693_169_093_06,
+    0.198_217_285_234_362_5,
+  ],
+  [
+    0.228_974_564_069_748_8,
+    0.691_738_521_836_506_4,
+    0.079_286_914_093_745,
+  ],
+  [0.0, 0.045_113_381_858_902_64, 1.043_944_368_900_976],
+];
+
+const A98_TO_XYZ: [[f64; 3]; 3] = [
+  [
+    0.576_669_042_910_130_5,
+    0.185_558_237_906_546_3,
+    0.188_228_646_234_994_7,
+  ],
+  [
+    0.297_344_975_250_536_05,
+    0.627_363_566_255_466_1,
+    0.075_291_458_493_997_88,
+  ],
+  [
+    0.027_031_361_386_412_34,
+    0.070_688_852_535_827_23,
+    0.991_337_536_837_638_8,
+  ],
+];
+
+/// ProPhoto RGB is relative to a D50 white point.
+const PROPHOTO_TO_XYZ: [[f64; 3]; 3] = [
+  [
+    0.797_766_644_900_642_3,
+    0.135_181_297_400_533_08,
+    0.031_347_734_128_392_2,
+  ],
+  [
+    0.288_074_828_819_401_3,
+    0.711_835_234_241_873,
+    0.000_089_936_938_725_64,
+  ],
+  [0.0, 0.0, 0.825_104_602_510_460_2],
+];
+
+const REC2020_TO_XYZ: [[f64; 3]; 3] = [
+  [
+    0.636_958_048_301_291_4,
+    0.144_616_903_586_208_32,
+    0.168_880_975_164_172_1,
+  ],
+  [
+    0.262_700_212_011_267_1,
+    0.677_998_071_518_870_8,
+    0.059_301_716_469_861_96,
+  ],
+  [0.0, 0.028_072_693_049_087_428, 1.060_985_057_710_791],
+];
+
+const OKLAB_TO_LMS: [[f64; 3]; 3] = [
+  [1.0, 0.396_337_777_4, 0.215_803_757_3],
+  [1.0, -0.105_561_345_8, -0.063_854_172_8],
+  [1.0, -0.089_484_177_5, -1.291_485_548],
+];
+
+const LMS_TO_LINEAR_SRGB: [[f64; 3]; 3] = [
+  [4.076_741_662_1, -3.307_711_591_3, 0.230_969_929_2],
+  [-1.268_438_004_6, 2.609_757_401_1, -0.341_319_396_5],
+  [-0.004_196_086_3, -0.703_418_614_7, 1.707_614_701],
+];
+
+/// The named colors of CSS, sorted by name.
+const NAMED: [(&str, u32); 148] = [
+  ("aliceblue", 0xf0f8ff),
+  ("antiquewhite", 0xfaebd7),
+  ("aqua", 0x00ffff),
+  ("aquamarine", 0x7fffd4),
+  ("azure", 0xf0ffff),
+  ("beige", 0xf5f5dc),
+  ("bisque", 0xffe4c4),
+  ("black", 0x000000),
+  ("blanchedalmond", 0xffebcd),
+  ("blue", 0x0000ff),
+  ("blueviolet", 0x8a2be2),
+  ("brown", 0xa52a2a),
+  ("burlywood", 0xdeb887),
+  ("cadetblue", 0x5f9ea0),
+  ("chartreuse", 0x7fff00),
+  ("chocolate", 0xd2691e),
+  ("coral", 0xff7f50),
+  ("cornflowerblue", 0x6495ed),
+  ("cornsilk", 0xfff8dc),
+  ("crimson", 0xdc143c),
+  ("cyan", 0x00ffff),
+  ("darkblue", 0x00008b),
+  ("darkcyan", 0x008b8b),
+  ("darkgoldenrod", 0xb8860b),
+  ("darkgray", 0xa9a9a9),
+  ("darkgreen", 0x006400),
+  ("darkgrey", 0xa9a9a9),
+  ("darkkhaki", 0xbdb76b),
+  ("darkmagenta", 0x8b008b),
+  ("darkolivegreen", 0x556b2f),
+  ("darkorange", 0xff8c00),
+  ("darkorchid", 0x9932cc),
+  ("darkred", 0x8b0000),
+  ("darksalmon", 0xe9967a),
+  ("darkseagreen", 0x8fbc8f),
+  ("darkslateblue", 0x483d8b),
+  ("darkslategray", 0x2f4f4f),
+  ("darkslategrey", 0x2f4f4f),
+  ("darkturquoise", 0x00ced1),
+  ("darkviolet", 0x9400d3),
+  ("deeppink", 0xff1493),
+  ("deepskyblue", 0x00bfff),
+  ("dimgray", 0x696969),
+  ("dimgrey", 0x696969),
+  ("dodgerblue", 0x1e90ff),
+  ("firebrick", 0xb22222),
+  ("floralwhite", 0xfffaf0),
+  ("forestgreen", 0x228b22),
+  ("fuchsia", 0xff00ff),
+  ("gainsboro", 0xdcdcdc),
+  ("ghostwhite", 0xf8f8ff),
+  ("gold", 0xffd700),
+  ("goldenrod", 0xdaa520),
+  ("gray", 0x808080),
+  ("green", 0x008000),
+  ("greenyellow", 0xadff2f),
+  ("grey", 0x808080),
+  ("honeydew", 0xf0fff0),
+  ("hotpink", 0xff69b4),
+  ("indianred", 0xcd5c5c),
+  ("indigo", 0x4b0082),
+  ("ivory", 0xfffff0),
+  ("khaki", 0xf0e68c),
+  ("lavender", 0xe6e6fa),
+  ("lavenderblush", 0xfff0f5),
+  ("lawngreen", 0x7cfc00),
+  ("lemonchiffon", 0xfffacd),
+  ("lightblue", 0xadd8e6),
+  ("lightcoral", 0xf08080),
+  ("lightcyan", 0xe0ffff),
+  ("lightgoldenrodyellow", 0xfafad2),
+  ("lightgray", 0xd3d3d3),
+  ("lightgreen", 0x90ee90),
+  ("lightgrey", 0xd3d3d3),
+  ("lightpink", 0xffb6c1),
+  ("lightsalmon", 0xffa07a),
+  ("lightseagreen", 0x20b2aa),
+  ("lightskyblue", 0x87cefa),
+  ("lightslategray", 0x778899),
+  ("lightslategrey", 0x778899),
+  ("lightsteelblue", 0xb0c4de),
+  ("lightyellow", 0xffffe0),
+  ("lime", 0x00ff00),
+  ("limegreen", 0x32cd32),
+  ("linen", 0xfaf0e6),
+  ("magenta", 0xff00ff),
+  ("maroon", 0x800000),
+  ("mediumaquamarine", 0x66cdaa),
+  ("mediumblue", 0x0000cd),
+  ("mediumorchid", 0xba55d3),
+  ("mediumpurple", 0x9370db),
+  ("mediumseagreen", 0x3cb371),
+  ("mediumslateblue", 0x7b68ee),
+  ("mediumspringgreen", 0x00fa9a),
+  ("mediumturquoise", 0x48d1cc),
+  ("mediumvioletred", 0xc71585),
+  ("midnightblue", 0x191970),
+  ("mintcream", 0xf5fffa),
+  ("mistyrose", 0xffe4e1),
+  ("moccasin", 0xffe4b5),
+  ("navajowhite", 0xffdead),
+  ("navy", 0x000080),
+  ("oldlace", 0xfdf5e6),
+  ("olive", 0x808000),
+  ("olivedrab", 0x6b8e23),
+  ("orange", 0xffa500),
+  ("orangered", 0xff4500),
+  ("orchid", 0xda70d6),
+  ("palegoldenrod", 0xeee8aa),
+  ("palegreen", 0x98fb98),
+  ("paleturquoise", 0xafeeee),
+  ("palevioletred", 0xdb7093),
+  ("papayawhip", 0xffefd5),
+  ("peachpuff", 0xffdab9),
+  ("peru", 0xcd853f),
+  ("pink", 0xffc0cb),
+  ("plum", 0xdda0dd),
+  ("powderblue", 0xb0e0e6),
+  ("purple", 0x800080),
+  ("rebeccapurple", 0x663399),
+  ("red", 0xff0000),
+  ("rosybrown", 0xbc8f8f),
+  ("royalblue", 0x4169e1),
+  ("saddlebrown", 0x8b4513),
+  ("salmon", 0xfa8072),
+  ("sandybrown", 0xf4a460),
+  ("seagreen", 0x2e8b57),
+  ("seashell", 0xfff5ee),
+  ("sienna", 0xa0522d),
+  ("silver", 0xc0c0c0),
+  ("skyblue", 0x87ceeb),
+  ("slateblue", 0x6a5acd),
+  ("slategray", 0x708090),
+  ("slategrey", 0x708090),
+  ("snow", 0xfffafa),
+  ("springgreen", 0x00ff7f),
+  ("steelblue", 0x4682b4),
+  ("tan", 0xd2b48c),
+  ("teal", 0x008080),
+  ("thistle", 0xd8bfd8),
+  ("tomato", 0xff6347),
+  ("turquoise", 0x40e0d0),
+  ("violet", 0xee82ee),
+  ("wheat", 0xf5deb3),
+  ("white", 0xffffff),
+  ("whitesmoke", 0xf5f5f5),
+  ("yellow", 0xffff00),
+  ("yellowgreen", 0x9acd32),
+];
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn hex(value: &str) -> String {
+    Color::parse(value).unwrap().to_hex()
+  }
+
+  #[test]
+  fn legacy_rgb_does_not_mix_numbers_and_percentages() {
+    assert_eq!(hex("rgb(255, 0, 0)"), "#ff0000");
+    assert_eq!(hex("rgb(100%, 0%, 0%)"), "#ff0000");
+    assert!(Color::parse("rgb(255, 0%, 0)").is_err());
+    assert!(Color::parse("rgb(100%, 0, 0)").is_err());
+    assert!(Color::parse("hsl(0, 100, 50%)").is_err());
+  }
+
+  #[test]
+  fn none_is_only_allowed_in_modern_syntax() {
+    assert_eq!(hex("rgb(none 0 0)"), "#000000");
+    assert_eq!(hex("rgb(255 0 0 / none)"), "#ff000000");
+    assert!(Color::parse("rgb(none, 0, 0)").is_err());
+    assert!(Color::parse("rgba(255, 0, 0, none)").is_err());
+  }
+
+  #[test]
+  fn hex_lengths() {
+    assert_eq!(hex("#F00"), "#ff0000");
+    assert_eq!(hex("#f008"), "#ff000088");
+    assert_eq!(hex("#ff0000"), "#ff0000");
+    assert_eq!(hex("#ff000080"), "#ff000080");
+    for value in &["#", "#f0", "#ff000", "#ff00000", "#ff0000000", "#ggg"] {
+      assert!(Color::parse(value).is_err(), "{}", value);
+    }
+  }
+
+  #[test]
+  fn color_function_needs_a_known_space() {
+    assert_eq!(hex("color(srgb 1 0 0)"), "#ff0000");
+    assert!(Color::parse("color(foo 1 0 0)").is_err());
+    assert!(Color::parse("color(1 0 0)").is_err());
+    assert!(Color::parse("color()").is_err());
+  }
+
+  #[test]
+  fn hwb_normalizes_whiteness_and_blackness() {
+    assert_eq!(hex("hwb(0 0% 0%)"), "#ff0000");
+    assert_eq!(hex("hwb(120 60% 60%)"), "#808080");
+    assert_eq!(hex("hwb(0 100% 0%)"), "#ffffff");
+    assert_eq!(hex("hwb(0 75% 25%)"), hex("hwb(240 75% 25%)"));
+  }
+
+  #[test]
+  fn reference_values() {
+    let cases = [
+      ("lab(54.29% 80.82 69.88)", "#ff0000"),
+      ("lch(54.29% 106.84 40.85)", "#ff0000"),
+      ("lab(46.2775% -47.5621 48.5837)", "#008000"),
+      ("lab(100% 0 0)", "#ffffff"),
+      ("oklab(62.8% 0.2249 0.1258)", "#ff0000"),
+      ("oklch(62.8% 0.2577 29.23)", "#ff0000"),
+      ("oklch(100% 0 0)", "#ffffff"),
+      ("color(display-p3 0.9175 0.2003 0.1386)", "#ff0000"),
+      ("color(display-p3 0.5 0.5 0.5)", "#808080"),
+    ];
+    for (value, expected) in cases.iter() {
+      assert_eq!(&hex(value), expected, "{}", value);
+    }
+  }
+
+  #[test]
+  fn to_hex_clips_out_of_gamut_colors() {
+    let color = Color::parse("color(display-p3 1 0 0)").unwrap();
+    assert!(!color.is_in_srgb_gamut());
+    assert_eq!(color.to_hex(), "#ff0000");
+    let color = Color::parse("lab(100% 0 0 / 50%)").unwrap();
+    assert_eq!(color.to_hex(), "#ffffff80");
+  }
+
+  #[test]
+  fn named_colors_are_sorted() {
+    for pair in NAMED.windows(2) {
+      assert!(pair[0].0 < pair[1].0, "{} >= {}", pair[0].0, pair[1].0);
+    }
+    for (name, _) in NAMED.iter() {
+      assert!(Color::parse(name).is_ok(), "{}", name);
+    }
+  }
+}
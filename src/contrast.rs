@@ -0,0 +1,291 @@
+#[cfg(feature = "image")]
+use image::RgbaImage;
+
+#[cfg(feature = "image")]
+use std::collections::HashMap;
+#[cfg(feature = "image")]
+use std::path::Path;
+
+#[cfg(feature = "image")]
+use super::assets::local_path;
+#[cfg(feature = "image")]
+use super::safe_zone::open_image;
+use super::{Color, Diagnostic, DiagnosticKind, Manifest};
+#[cfg(feature = "image")]
+use super::{Error, IconQuery};
+
+/// The contrast ratio with white from which user agents draw white text on
+/// the theme color, like Chrome on Android does.
+const LIGHT_TEXT_MIN: f64 = 3.0;
+/// The minimum contrast ratio of text, from WCAG 2 level AA.
+const TEXT_MIN: f64 = 4.5;
+/// The minimum contrast ratio of graphical objects, from WCAG 2 level AA.
+#[cfg(feature = "image")]
+const GRAPHICS_MIN: f64 = 3.0;
+/// The icon size, in CSS pixels, Chrome looks for to show on splash screens.
+#[cfg(feature = "image")]
+const SPLASH_ICON: u32 = 128;
+
+impl<'a> Manifest<'a> {
+  /// Check that system text drawn on `theme_color`, such as the status bar,
+  /// is readable.
+  ///
+  /// User agents draw white text on the theme color if their contrast ratio
+  /// is at least 3:1, and black text otherwise. If the contrast ratio of that
+  /// text is below 4.5:1, the WCAG minimum for text, a warning with the
+  /// measured ratio is reported. A missing or invalid `theme_color` is not
+  /// checked; `Manifest::validate` reports invalid colors.
+  ///
+  /// ## Example
+  /// ```rust
+  /// # extern crate webmanifest;
+  /// # use webmanifest::{DiagnosticKind, Manifest};
+  /// let manifest = Manifest::builder("My Cool Application").theme_color("#2196f3");
+  /// let diagnostics = manifest.check_contrast();
+  /// match diagnostics[0].get_kind() {
+  ///   DiagnosticKind::LowContrast { color, ratio, .. } => {
+  ///     assert_eq!(color, "#ffffff");
+  ///     assert!(*ratio < 4.5);
+  ///   }
+  ///   kind => panic!("unexpected {:?}", kind),
+  /// }
+  /// ```
+  #[must_use]
+  pub fn check_contrast(&self) -> Vec<Diagnostic> {
+    let mut diagnostics = vec![];
+    if let Some(theme_color) = self.get_theme_color_value() {
+      let white = Color::rgb(255, 255, 255);
+      let black = Color::rgb(0, 0, 0);
+      let text = if theme_color.contrast_ratio(white) >= LIGHT_TEXT_MIN {
+        white
+      } else {
+        black
+      };
+      let ratio = theme_color.contrast_ratio(text);
+      if ratio < TEXT_MIN {
+        let kind = DiagnosticKind::LowContrast {
+          color: text.to_hex(),
+          ratio,
+          min: TEXT_MIN,
+        };
+        diagnostics.push(Diagnostic::warning("theme_color", kind));
+      }
+    }
+    diagnostics
+  }
+
+  /// Check that the splash screen icon stands out from `background_color`.
+  ///
+  /// Requires the `image` feature.
+  ///
+  /// Splash screens show the icon that `Manifest::select_icon` picks for 128
+  /// CSS pixels on `background_color`. The dominant color along the visible
+  /// edge of that icon, the outline of its opaque pixels, is compared with
+  /// the background. A contrast ratio below 3:1, the WCAG minimum for
+  /// graphical objects, is reported as a warning with the measured ratio.
+  ///
+  /// The icon `src` is resolved into `dir` like `Manifest::check_assets`
+  /// does. A missing or invalid `background_color`, and icon files that are
+  /// missing or can't be decoded (including SVGs), are not checked.
+  ///
+  /// ## Errors
+  /// Fails if the icon file exists but can't be read.
+  ///
+  /// ## Example
+  /// ```rust,no_run
+  /// # extern crate webmanifest;
+  /// # use webmanifest::{Icon, Manifest};
+  /// # fn main() -> Result<(), webmanifest::Error> {
+  /// let manifest = Manifest::builder("My Cool Application")
+  ///   .bg_color("#fff")
+  ///   .icon(Icon::new("icons/icon-192.png", (192, 192)));
+  /// for diagnostic in manifest.check_splash_contrast("public")? {
+  ///   println!("{}", diagnostic);
+  /// }
+  /// # Ok(())}
+  /// ```
+  #[cfg(feature = "image")]
+  pub fn check_splash_contrast(
+    &self,
+    dir: impl AsRef<Path>,
+  ) -> Result<Vec<Diagnostic>, Error> {
+    let mut diagnostics = vec![];
+    let background = match self.get_bg_color_value() {
+      Some(background) => background,
+      None => return Ok(diagnostics),
+    };
+    let icon = match self.select_icon(&IconQuery::new(SPLASH_ICON)) {
+      Some(icon) => icon,
+      None => return Ok(diagnostics),
+    };
+    let image = match local_path(dir.as_ref(), icon.get_src()) {
+      Ok(Some(path)) => open_image(&path)?,
+      _ => None,
+    };
+    let edge = match image.as_ref().and_then(edge_color) {
+      Some(edge) => edge,
+      None => return Ok(diagnostics),
+    };
+    let ratio = background.contrast_ratio(edge);
+    if ratio < GRAPHICS_MIN {
+      let kind = DiagnosticKind::LowContrast {
+        color: edge.to_hex(),
+        ratio,
+        min: GRAPHICS_MIN,
+      };
+      diagnostics.push(Diagnostic::warning("background_color", kind));
+    }
+    Ok(diagnostics)
+  }
+}
+
+/// Find the dominant color along the visible edge of the image: the opaque
+/// pixels on the border of the image or next to a transparent pixel. Colors
+/// are grouped into buckets of 16 values per channel, and the average of the
+/// largest bucket is returned.
+#[cfg(feature = "image")]
+fn edge_color(image: &RgbaImage) -> Option<Color> {
+  let (width, height) = image.dimensions();
+  let opaque = |x: u32, y: u32| image.get_pixel(x, y)[3] >= 128;
+  let mut buckets = HashMap::new();
+  for (x, y, pixel) in image.enumerate_pixels() {
+    if pixel[3] < 128 {
+      continue;
+    }
+    let edge = x == 0
+      || y == 0
+      || x + 1 == width
+      || y + 1 == height
+      || !opaque(x - 1, y)
+      || !opaque(x + 1, y)
+      || !opaque(x, y - 1)
+      || !opaque(x, y + 1);
+    if !edge {
+      continue;
+    }
+    let bucket = [pixel[0] >> 4, pixel[1] >> 4, pixel[2] >> 4];
+    let (count, sums) = buckets.entry(bucket).or_insert((0u64, [0u64; 3]));
+    *count += 1;
+    for (sum, channel) in sums.iter_mut().zip(pixel.0.iter()) {
+      *sum += u64::from(*channel);
+    }
+  }
+  let (_, (count, sums)) = buckets
+    .into_iter()
+    .max_by_key(|&(bucket, (count, _))| (count, bucket))?;
+  let [red, green, blue] = sums.map(|sum| (sum / count) as u8);
+  Some(Color::rgb(red, green, blue))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn readable_theme_colors_pass() {
+    for color in &["#000080", "#ffff00", "white", "black"] {
+      let manifest = Manifest::builder("App").theme_color(*color);
+      assert!(manifest.check_contrast().is_empty(), "{}", color);
+    }
+  }
+
+  #[test]
+  fn reports_low_contrast_theme_color() {
+    let manifest = Manifest::builder("App").theme_color("#808080");
+    let diagnostics = manifest.check_contrast();
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].get_member(), "theme_color");
+    match diagnostics[0].get_kind() {
+      DiagnosticKind::LowContrast { color, ratio, min } => {
+        assert_eq!(color, "#ffffff");
+        assert!((*ratio - 3.95).abs() < 0.01, "{}", ratio);
+        assert_eq!(*min, TEXT_MIN);
+      }
+      kind => panic!("unexpected {:?}", kind),
+    }
+  }
+
+  #[test]
+  fn skips_missing_or_invalid_theme_color() {
+    assert!(Manifest::builder("App").check_contrast().is_empty());
+    let manifest = Manifest::builder("App").theme_color("not a color");
+    assert!(manifest.check_contrast().is_empty());
+  }
+
+  #[cfg(feature = "image")]
+  mod splash {
+    use super::super::super::test_util::temp_dir;
+    use super::super::super::Icon;
+    use super::super::*;
+
+    use image::Rgba;
+    use std::fs;
+
+    /// Write a 128x128 icon: a circle with an outline of `edge` filled with
+    /// `fill`, on a transparent background.
+    fn write_icon(dir: &Path, edge: [u8; 3], fill: [u8; 3]) {
+      let mut image = RgbaImage::new(128, 128);
+      for (x, y, pixel) in image.enumerate_pixels_mut() {
+        let dx = f64::from(x) - 63.5;
+        let dy = f64::from(y) - 63.5;
+        let distance = (dx * dx + dy * dy).sqrt();
+        let [r, g, b] = if distance < 50.0 { fill } else { edge };
+        if distance < 60.0 {
+          *pixel = Rgba([r, g, b, 255]);
+        }
+      }
+      image.save(dir.join("icon.png")).unwrap();
+    }
+
+    fn splash(dir: &Path, background: &str) -> Vec<Diagnostic> {
+      Manifest::builder("App")
+        .bg_color(background)
+        .icon(Icon::new("icon.png", (128, 128)))
+        .check_splash_contrast(dir)
+        .unwrap()
+    }
+
+    #[test]
+    fn reports_icon_edge_close_to_background() {
+      let dir = temp_dir("contrast-low");
+      write_icon(&dir, [240, 240, 240], [0, 0, 0]);
+      let diagnostics = splash(&dir, "#fff");
+      assert_eq!(diagnostics.len(), 1);
+      assert_eq!(diagnostics[0].get_member(), "background_color");
+      match diagnostics[0].get_kind() {
+        DiagnosticKind::LowContrast { color, ratio, min } => {
+          assert_eq!(color, "#f0f0f0");
+          assert!(*ratio < GRAPHICS_MIN);
+          assert_eq!(*min, GRAPHICS_MIN);
+        }
+        kind => panic!("unexpected {:?}", kind),
+      }
+      fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn judges_icon_by_its_edge() {
+      let dir = temp_dir("contrast-edge");
+      write_icon(&dir, [0, 0, 0], [255, 255, 255]);
+      assert!(splash(&dir, "#fff").is_empty());
+      assert_eq!(splash(&dir, "#000").len(), 1);
+      fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn skips_splash_without_background_or_icon() {
+      let dir = temp_dir("contrast-skipped");
+      write_icon(&dir, [255, 255, 255], [255, 255, 255]);
+      let manifest =
+        Manifest::builder("App").icon(Icon::new("icon.png", (128, 128)));
+      assert!(manifest.check_splash_contrast(&dir).unwrap().is_empty());
+      let manifest = Manifest::builder("App").bg_color("#fff");
+      assert!(manifest.check_splash_contrast(&dir).unwrap().is_empty());
+      let manifest = Manifest::builder("App")
+        .bg_color("#fff")
+        .icon(Icon::new("missing.png", (128, 128)));
+      assert!(manifest.check_splash_contrast(&dir).unwrap().is_empty());
+      fs::remove_dir_all(dir).unwrap();
+    }
+  }
+}
@@ -0,0 +1,77 @@
+//! Helpers for `Cow<str>` members.
+//!
+//! Serde only borrows a `Cow<str>` when it is the direct type of a field marked
+//! `#[serde(borrow)]`. These helpers extend that to `Option<Cow<str>>` and
+//! lists of strings, so strings without escape sequences are never copied.
+
+use serde::{Deserialize, Deserializer};
+
+use std::borrow::Cow;
+
+/// A string that borrows from the input whenever it can.
+#[derive(Deserialize)]
+pub(crate) struct CowStr<'a>(#[serde(borrow)] pub(crate) Cow<'a, str>);
+
+/// Deserialize an optional string, borrowing from the input when possible.
+pub(crate) fn option<'de, D>(
+  deserializer: D,
+) -> Result<Option<Cow<'de, str>>, D::Error>
+where
+  D: Deserializer<'de>,
+{
+  let value = Option::<CowStr<'de>>::deserialize(deserializer)?;
+  Ok(value.map(|value| value.0))
+}
+
+/// A value that may be given as a single entry or as an array.
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub(crate) enum OneOrMany<T> {
+  One(T),
+  Many(Vec<T>),
+}
+
+impl<T> OneOrMany<T> {
+  /// Convert into a list of entries.
+  pub(crate) fn into_vec(self) -> Vec<T> {
+    match self {
+      OneOrMany::One(value) => vec![value],
+      OneOrMany::Many(values) => values,
+    }
+  }
+}
+
+/// Deserialize a single entry or an array of entries.
+pub(crate) fn one_or_many<'de, D, T>(
+  deserializer: D,
+) -> Result<Vec<T>, D::Error>
+where
+  D: Deserializer<'de>,
+  T: Deserialize<'de>,
+{
+  Ok(OneOrMany::deserialize(deserializer)?.into_vec())
+}
+
+/// Deserialize a string or an array of strings, borrowing from the input when
+/// possible.
+pub(crate) fn list<'de, D>(
+  deserializer: D,
+) -> Result<Vec<Cow<'de, str>>, D::Error>
+where
+  D: Deserializer<'de>,
+{
+  let values: Vec<CowStr<'de>> = one_or_many(deserializer)?;
+  Ok(values.into_iter().map(|value| value.0).collect())
+}
+
+/// Detach a string from the data it borrows from.
+pub(crate) fn owned(value: Cow<str>) -> Cow<'static, str> {
+  Cow::Owned(value.into_owned())
+}
+
+/// Detach an optional string from the data it borrows from.
+pub(crate) fn owned_option(
+  value: Option<Cow<str>>,
+) -> Option<Cow<'static, str>> {
+  value.map(owned)
+}
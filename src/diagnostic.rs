@@ -0,0 +1,450 @@
+use std::fmt;
+
+use super::{Direction, FormFactor, ShareEnctype, ShareMethod};
+
+/// How severe a `Diagnostic` is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+  /// The manifest can still be used, but a member was dropped or changed.
+  Warning,
+  /// The manifest will not behave as intended.
+  Error,
+}
+
+/// The kind of problem a `Diagnostic` describes.
+///
+/// New kinds are added as more of the spec is checked, so matches need a
+/// wildcard arm.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum DiagnosticKind {
+  /// A required member was not present.
+  MissingMember,
+  /// A member had the wrong JSON type and was ignored.
+  InvalidType {
+    /// The JSON type the member should have had.
+    expected: &'static str,
+  },
+  /// A member had a value that is not allowed and was ignored.
+  InvalidValue {
+    /// The value that was found.
+    value: String,
+  },
+  /// A color member is not a valid CSS color and was ignored.
+  InvalidColor {
+    /// The value that was found.
+    value: String,
+  },
+  /// `lang` is not a well-formed language tag and was ignored.
+  InvalidLanguageTag {
+    /// The value that was found.
+    value: String,
+    /// Why the value is not well-formed.
+    reason: &'static str,
+  },
+  /// A URL member could not be parsed and was ignored.
+  InvalidUrl {
+    /// The value that was found.
+    value: String,
+  },
+  /// A URL member has a different origin than required and was ignored.
+  CrossOrigin {
+    /// The resolved URL.
+    url: String,
+    /// The origin the URL had to match.
+    origin: String,
+  },
+  /// A URL lies outside the navigation scope.
+  OutOfScope {
+    /// The resolved URL.
+    url: String,
+    /// The resolved scope.
+    scope: String,
+  },
+  /// A text member is longer than recommended.
+  TooLong {
+    /// The measured length, in user-perceived characters.
+    length: usize,
+    /// The maximum length.
+    max: usize,
+  },
+  /// A file referenced by the manifest does not exist.
+  MissingFile {
+    /// The path that was checked.
+    path: String,
+  },
+  /// A URL does not map to a file inside the directory it is resolved into,
+  /// e.g. because it contains an encoded `..` or `/`.
+  InvalidPath {
+    /// The value that was found.
+    value: String,
+  },
+  /// A file referenced by the manifest is not an image in a known format.
+  UnknownImageFormat {
+    /// The path that was checked.
+    path: String,
+  },
+  /// The contents of an image don't match its declared MIME type.
+  TypeMismatch {
+    /// The declared MIME type.
+    declared: String,
+    /// The MIME type matching the contents.
+    actual: String,
+  },
+  /// The dimensions of an image don't match its declared sizes.
+  SizeMismatch {
+    /// The declared sizes.
+    declared: String,
+    /// The dimensions of the image, in the `sizes` syntax.
+    actual: String,
+  },
+  /// An image is not square.
+  NotSquare {
+    /// The width in pixels.
+    width: u32,
+    /// The height in pixels.
+    height: u32,
+  },
+  /// A URL has a query string or fragment that will be ignored.
+  QueryOrFragment {
+    /// The resolved URL.
+    url: String,
+  },
+  /// An image is smaller or larger than allowed.
+  DimensionsOutOfRange {
+    /// The width in pixels.
+    width: u32,
+    /// The height in pixels.
+    height: u32,
+    /// The smallest allowed dimension in pixels.
+    min: u32,
+    /// The largest allowed dimension in pixels.
+    max: u32,
+  },
+  /// An image is more elongated than allowed.
+  AspectRatio {
+    /// The width in pixels.
+    width: u32,
+    /// The height in pixels.
+    height: u32,
+    /// The largest allowed ratio of the longest to the shortest side.
+    max: f64,
+  },
+  /// No screenshot has the given form factor.
+  MissingFormFactor {
+    /// The form factor that is missing.
+    form_factor: FormFactor,
+  },
+  /// A list has more entries than a platform shows.
+  TooMany {
+    /// The number of entries.
+    count: usize,
+    /// The number of entries that are shown.
+    max: usize,
+  },
+  /// No icon has the size a platform needs.
+  MissingIconSize {
+    /// The needed width in pixels.
+    width: u32,
+    /// The needed height in pixels.
+    height: u32,
+  },
+  /// A share target `enctype` can't be used with its `method`.
+  UnsupportedEnctype {
+    /// The method of the share target.
+    method: ShareMethod,
+    /// The encoding of the share target.
+    enctype: ShareEnctype,
+  },
+  /// A share target accepts files but is not a `POST` request with
+  /// `multipart/form-data` encoding.
+  FilesRequireMultipart,
+  /// A protocol handler `protocol` is not on the safelist and does not start
+  /// with `web+`.
+  UnsupportedProtocol {
+    /// The protocol that was found.
+    protocol: String,
+  },
+  /// A URL template does not contain `%s` exactly once.
+  Placeholder {
+    /// The number of `%s` found.
+    count: usize,
+  },
+  /// A MIME type is not of the form `type/subtype`.
+  InvalidMimeType {
+    /// The value that was found.
+    value: String,
+  },
+  /// A file extension does not start with a dot.
+  InvalidExtension {
+    /// The value that was found.
+    value: String,
+  },
+  /// A related application `platform` is not one user agents know about.
+  UnknownPlatform {
+    /// The platform that was found.
+    platform: String,
+  },
+  /// Related applications are preferred, but none of them can be used.
+  NoUsableRelated,
+  /// An icon `purpose` has no known purpose, so the icon was ignored.
+  NoUsablePurpose,
+  /// Two colors have a lower contrast ratio than recommended.
+  LowContrast {
+    /// The color the member was compared with, in hex notation.
+    color: String,
+    /// The measured contrast ratio, from 1 to 21.
+    ratio: f64,
+    /// The recommended minimum contrast ratio.
+    min: f64,
+  },
+  /// `dir` contradicts the direction of the script `lang` is written in.
+  ConflictingDirection {
+    /// The `lang` value.
+    lang: String,
+    /// The direction the language is written in.
+    expected: Direction,
+  },
+  /// A maskable icon has transparent pixels on its edges.
+  TransparentEdges,
+  /// A maskable icon has content outside the safe zone, which may be cropped.
+  OutsideSafeZone {
+    /// The share of the pixels outside the safe zone that are content, from 0
+    /// to 100.
+    percentage: f64,
+  },
+}
+
+impl fmt::Display for DiagnosticKind {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      DiagnosticKind::MissingMember => write!(f, "member is missing"),
+      DiagnosticKind::InvalidType { expected } => {
+        write!(f, "expected {}, ignoring member", expected)
+      }
+      DiagnosticKind::InvalidValue { value } => {
+        write!(f, "unsupported value `{}`, ignoring member", value)
+      }
+      DiagnosticKind::InvalidColor { value } => {
+        write!(f, "`{}` is not a valid CSS color, ignoring member", value)
+      }
+      DiagnosticKind::InvalidLanguageTag { value, reason } => write!(
+        f,
+        "`{}` is not a valid language tag: {}, ignoring member",
+        value, reason
+      ),
+      DiagnosticKind::InvalidUrl { value } => {
+        write!(f, "`{}` is not a valid URL, ignoring member", value)
+      }
+      DiagnosticKind::CrossOrigin { url, origin } => write!(
+        f,
+        "`{}` is not same-origin with `{}`, ignoring member",
+        url, origin
+      ),
+      DiagnosticKind::OutOfScope { url, scope } => {
+        write!(f, "`{}` is not within scope `{}`", url, scope)
+      }
+      DiagnosticKind::TooLong { length, max } => write!(
+        f,
+        "{} characters long, the recommended maximum is {}",
+        length, max
+      ),
+      DiagnosticKind::MissingFile { path } => {
+        write!(f, "file `{}` does not exist", path)
+      }
+      DiagnosticKind::InvalidPath { value } => write!(
+        f,
+        "`{}` does not map to a file inside the directory, ignoring member",
+        value
+      ),
+      DiagnosticKind::UnknownImageFormat { path } => {
+        write!(f, "file `{}` is not a recognized image", path)
+      }
+      DiagnosticKind::TypeMismatch { declared, actual } => write!(
+        f,
+        "declared as `{}`, but the contents are `{}`",
+        declared, actual
+      ),
+      DiagnosticKind::SizeMismatch { declared, actual } => write!(
+        f,
+        "declared as `{}`, but the image is `{}`",
+        declared, actual
+      ),
+      DiagnosticKind::NotSquare { width, height } => {
+        write!(f, "image is {}x{}, which is not square", width, height)
+      }
+      DiagnosticKind::QueryOrFragment { url } => write!(
+        f,
+        "`{}` has a query string or fragment, which is ignored",
+        url
+      ),
+      DiagnosticKind::DimensionsOutOfRange {
+        width,
+        height,
+        min,
+        max,
+      } => write!(
+        f,
+        "image is {}x{}, dimensions must be between {} and {} pixels",
+        width, height, min, max
+      ),
+      DiagnosticKind::AspectRatio { width, height, max } => write!(
+        f,
+        "image is {}x{}, the longest side may be at most {} times the \
+         shortest",
+        width, height, max
+      ),
+      DiagnosticKind::MissingFormFactor { form_factor } => {
+        let form_factor = match form_factor {
+          FormFactor::Wide => "wide",
+          FormFactor::Narrow => "narrow",
+        };
+        write!(f, "no screenshot has the `{}` form factor", form_factor)
+      }
+      DiagnosticKind::TooMany { count, max } => {
+        write!(f, "{} entries, but only the first {} are shown", count, max)
+      }
+      DiagnosticKind::MissingIconSize { width, height } => {
+        write!(f, "no icon is {}x{}", width, height)
+      }
+      DiagnosticKind::UnsupportedEnctype { method, enctype } => write!(
+        f,
+        "`{}` can't be used with method `{}`",
+        enctype.as_str(),
+        method.as_str()
+      ),
+      DiagnosticKind::FilesRequireMultipart => write!(
+        f,
+        "sharing files requires method `POST` and enctype \
+         `multipart/form-data`"
+      ),
+      DiagnosticKind::UnsupportedProtocol { protocol } => write!(
+        f,
+        "`{}` is not a safelisted scheme and does not start with `web+`",
+        protocol
+      ),
+      DiagnosticKind::Placeholder { count } => write!(
+        f,
+        "URL template must contain `%s` exactly once, found {}",
+        count
+      ),
+      DiagnosticKind::InvalidMimeType { value } => {
+        write!(f, "`{}` is not a valid MIME type", value)
+      }
+      DiagnosticKind::InvalidExtension { value } => {
+        write!(f, "`{}` is not a file extension starting with `.`", value)
+      }
+      DiagnosticKind::UnknownPlatform { platform } => {
+        write!(f, "`{}` is not a known platform", platform)
+      }
+      DiagnosticKind::NoUsablePurpose => {
+        write!(f, "no known purpose, ignoring icon")
+      }
+      DiagnosticKind::NoUsableRelated => write!(
+        f,
+        "related applications are preferred, but none has a known platform \
+         and a `url` or `id`"
+      ),
+      DiagnosticKind::LowContrast { color, ratio, min } => write!(
+        f,
+        "contrast ratio with `{}` is {:.2}:1, the recommended minimum is {}:1",
+        color, ratio, min
+      ),
+      DiagnosticKind::ConflictingDirection { lang, expected } => {
+        let (written, dir) = match expected {
+          Direction::Rtl => ("right-to-left", "rtl"),
+          _ => ("left-to-right", "ltr"),
+        };
+        write!(
+          f,
+          "`{}` is written {}, expected `dir` to be `{}`",
+          lang, written, dir
+        )
+      }
+      DiagnosticKind::TransparentEdges => {
+        write!(f, "maskable icon has transparent edges")
+      }
+      DiagnosticKind::OutsideSafeZone { percentage } => write!(
+        f,
+        "{:.1}% of the area outside the safe zone is content, which may be \
+         cropped",
+        percentage
+      ),
+    }
+  }
+}
+
+/// A warning or error found while processing a manifest.
+///
+/// ## Example
+/// ```rust
+/// # extern crate webmanifest;
+/// # use webmanifest::{Manifest, Severity};
+/// # fn main() -> Result<(), webmanifest::Error> {
+/// let json = r#"{ "name": "My Cool Application", "display": "fullest" }"#;
+/// let (_, diagnostics) = Manifest::process(json)?;
+/// assert_eq!(diagnostics[0].get_member(), "display");
+/// assert_eq!(diagnostics[0].get_severity(), Severity::Warning);
+/// # Ok(())}
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+  severity: Severity,
+  member: String,
+  kind: DiagnosticKind,
+}
+
+impl Diagnostic {
+  /// Create a new `Diagnostic` with `Severity::Warning`.
+  #[must_use]
+  #[inline]
+  pub fn warning(member: impl Into<String>, kind: DiagnosticKind) -> Self {
+    Self {
+      severity: Severity::Warning,
+      member: member.into(),
+      kind,
+    }
+  }
+
+  /// Create a new `Diagnostic` with `Severity::Error`.
+  #[must_use]
+  #[inline]
+  pub fn error(member: impl Into<String>, kind: DiagnosticKind) -> Self {
+    Self {
+      severity: Severity::Error,
+      member: member.into(),
+      kind,
+    }
+  }
+
+  /// Get the severity.
+  #[must_use]
+  #[inline]
+  pub fn get_severity(&self) -> Severity {
+    self.severity
+  }
+
+  /// Get the path of the member this diagnostic refers to, e.g. `icons[2].src`.
+  #[must_use]
+  #[inline]
+  pub fn get_member(&self) -> &str {
+    &self.member
+  }
+
+  /// Get the kind of problem.
+  #[must_use]
+  #[inline]
+  pub fn get_kind(&self) -> &DiagnosticKind {
+    &self.kind
+  }
+}
+
+impl fmt::Display for Diagnostic {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    let severity = match self.severity {
+      Severity::Warning => "warning",
+      Severity::Error => "error",
+    };
+    write!(f, "{}: `{}`: {}", severity, self.member, self.kind)
+  }
+}
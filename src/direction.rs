@@ -4,9 +4,8 @@
 /// ## Example
 /// ```rust
 /// # extern crate webmanifest;
-/// # extern crate failure;
 /// # use webmanifest::{Manifest, Direction};
-/// # fn main() -> Result<(), failure::Error> {
+/// # fn main() -> Result<(), webmanifest::Error> {
 /// let name = "My Cool Application";
 /// let lang = "en-US";
 /// let manifest = Manifest::builder(name)
@@ -14,7 +13,7 @@
 ///   .build()?;
 /// # Ok(())}
 /// ```
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Direction {
   /// left-to-right
   #[serde(rename = "ltr")]
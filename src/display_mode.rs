@@ -1,21 +1,22 @@
+use super::Manifest;
+
 /// Defines the developers’ preferred display mode for the website.
 /// ## Example
 /// ```rust
 /// # extern crate webmanifest;
-/// # extern crate failure;
 /// # use webmanifest::{Manifest, DisplayMode};
-/// # fn main() -> Result<(), failure::Error> {
+/// # fn main() -> Result<(), webmanifest::Error> {
 /// let name = "My Cool Application";
 /// let manifest = Manifest::builder(name)
 ///   .display_mode(DisplayMode::Standalone)
 ///   .build()?;
 /// # Ok(())}
 /// ```
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum DisplayMode {
   /// All of the available display area is used and no user agent chrome is
-  /// shown.
-  #[serde(rename = "full-screen")]
+  /// shown. `full-screen` is accepted as an alias when deserializing.
+  #[serde(rename = "fullscreen", alias = "full-screen")]
   FullScreen,
   /// The application will look and feel like a standalone application. This can
   /// include the application having a different window, its own icon in the
@@ -33,4 +34,108 @@ pub enum DisplayMode {
   /// depending on the browser and platform. This is the default.
   #[serde(rename = "browser")]
   Browser,
+  /// Like `Standalone`, but the application draws into the title bar area
+  /// and the user agent only overlays the window controls. Only valid in
+  /// `display_override`.
+  #[serde(rename = "window-controls-overlay")]
+  WindowControlsOverlay,
+  /// The application can open several documents in tabs of one standalone
+  /// window. Only valid in `display_override`.
+  #[serde(rename = "tabbed")]
+  Tabbed,
+  /// The application opens in a small always-on-top window. Only valid in
+  /// `display_override`.
+  #[serde(rename = "picture-in-picture")]
+  PictureInPicture,
+}
+
+impl DisplayMode {
+  /// Get the keyword of the mode, as it appears in a manifest.
+  #[must_use]
+  pub fn as_str(self) -> &'static str {
+    match self {
+      DisplayMode::FullScreen => "fullscreen",
+      DisplayMode::Standalone => "standalone",
+      DisplayMode::MinimalUi => "minimal-ui",
+      DisplayMode::Browser => "browser",
+      DisplayMode::WindowControlsOverlay => "window-controls-overlay",
+      DisplayMode::Tabbed => "tabbed",
+      DisplayMode::PictureInPicture => "picture-in-picture",
+    }
+  }
+
+  /// Get the mode a user agent falls back to if it does not support this
+  /// one: `fullscreen`, `standalone`, `minimal-ui` and then `browser`.
+  /// `browser` and the modes that are only valid in `display_override` have
+  /// no fallback.
+  #[must_use]
+  pub fn fallback(self) -> Option<DisplayMode> {
+    match self {
+      DisplayMode::FullScreen => Some(DisplayMode::Standalone),
+      DisplayMode::Standalone => Some(DisplayMode::MinimalUi),
+      DisplayMode::MinimalUi => Some(DisplayMode::Browser),
+      _ => None,
+    }
+  }
+
+  /// Check if the mode is only valid in `display_override`, not in
+  /// `display`.
+  #[must_use]
+  pub fn is_override_only(self) -> bool {
+    matches!(
+      self,
+      DisplayMode::WindowControlsOverlay
+        | DisplayMode::Tabbed
+        | DisplayMode::PictureInPicture
+    )
+  }
+}
+
+impl<'a> Manifest<'a> {
+  /// Compute the display mode a user agent that supports the modes in
+  /// `supported` uses: the first supported mode of `display_override`, or
+  /// else `display` and its fallbacks. Every user agent supports `browser`,
+  /// so it is the result if nothing else applies, or if `display` is a mode
+  /// that is only valid in `display_override`.
+  ///
+  /// ## Example
+  /// ```rust
+  /// # extern crate webmanifest;
+  /// # use webmanifest::{Manifest, DisplayMode};
+  /// let manifest = Manifest::builder("My Cool Application")
+  ///   .display_mode(DisplayMode::FullScreen)
+  ///   .display_override(DisplayMode::WindowControlsOverlay);
+  ///
+  /// let supported = [DisplayMode::Standalone];
+  /// let mode = manifest.effective_display_mode(&supported);
+  /// assert_eq!(mode, DisplayMode::Standalone);
+  ///
+  /// let supported = [DisplayMode::WindowControlsOverlay];
+  /// let mode = manifest.effective_display_mode(&supported);
+  /// assert_eq!(mode, DisplayMode::WindowControlsOverlay);
+  /// ```
+  #[must_use]
+  pub fn effective_display_mode(
+    &self,
+    supported: &[DisplayMode],
+  ) -> DisplayMode {
+    let preferred = self
+      .display_override
+      .iter()
+      .find(|mode| supported.contains(mode));
+    if let Some(mode) = preferred {
+      return *mode;
+    }
+    let mut mode = match self.display_mode {
+      Some(mode) if !mode.is_override_only() => mode,
+      _ => DisplayMode::Browser,
+    };
+    while !supported.contains(&mode) {
+      match mode.fallback() {
+        Some(fallback) => mode = fallback,
+        None => break,
+      }
+    }
+    mode
+  }
 }
@@ -0,0 +1,94 @@
+#[cfg(feature = "image")]
+use image;
+use serde_json;
+
+use std::error::Error as StdError;
+use std::{fmt, io};
+
+use super::{Diagnostic, Severity};
+
+/// The error type for this crate.
+///
+/// New variants may be added, and some only exist with a feature enabled, so
+/// matches need a wildcard arm.
+///
+/// ## Example
+/// ```rust
+/// # extern crate webmanifest;
+/// # use webmanifest::{Error, Manifest};
+/// match Manifest::parse("{ \"short_name\": \"Cool App\" }") {
+///   Err(Error::Parse(err)) => println!("not a manifest: {}", err),
+///   Err(err) => println!("{}", err),
+///   Ok(_) => unreachable!(),
+/// }
+/// ```
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+  /// The manifest could not be serialized to JSON.
+  Serialize(serde_json::Error),
+  /// The input is not valid JSON, or does not have the shape of a manifest.
+  Parse(serde_json::Error),
+  /// A value does not follow the syntax of its member.
+  InvalidValue(Diagnostic),
+  /// Validation found at least one `Severity::Error` diagnostic. Holds all
+  /// diagnostics, including warnings.
+  Validation(Vec<Diagnostic>),
+  /// A share target request could not be parsed.
+  InvalidRequest(&'static str),
+  /// Reading or writing a file failed.
+  Io(io::Error),
+  /// Decoding or encoding an image failed. Requires the `image` feature.
+  #[cfg(feature = "image")]
+  Image(image::ImageError),
+}
+
+impl fmt::Display for Error {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Error::Serialize(err) => {
+        write!(f, "failed to serialize manifest: {}", err)
+      }
+      Error::Parse(err) => write!(f, "failed to parse manifest: {}", err),
+      Error::InvalidValue(diagnostic) => write!(f, "{}", diagnostic),
+      Error::Validation(diagnostics) => {
+        let errors = diagnostics
+          .iter()
+          .filter(|d| d.get_severity() == Severity::Error)
+          .count();
+        write!(f, "manifest validation failed with {} error(s)", errors)?;
+        for diagnostic in diagnostics {
+          write!(f, "\n{}", diagnostic)?;
+        }
+        Ok(())
+      }
+      Error::InvalidRequest(reason) => {
+        write!(f, "invalid share request: {}", reason)
+      }
+      Error::Io(err) => write!(f, "I/O error: {}", err),
+      #[cfg(feature = "image")]
+      Error::Image(err) => write!(f, "image error: {}", err),
+    }
+  }
+}
+
+impl StdError for Error {
+  fn source(&self) -> Option<&(dyn StdError + 'static)> {
+    match self {
+      Error::Serialize(err) | Error::Parse(err) => Some(err),
+      Error::InvalidValue(_)
+      | Error::Validation(_)
+      | Error::InvalidRequest(_) => None,
+      Error::Io(err) => Some(err),
+      #[cfg(feature = "image")]
+      Error::Image(err) => Some(err),
+    }
+  }
+}
+
+impl From<io::Error> for Error {
+  #[inline]
+  fn from(err: io::Error) -> Self {
+    Error::Io(err)
+  }
+}
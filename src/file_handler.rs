@@ -0,0 +1,422 @@
+use mime_guess;
+use serde::de::{MapAccess, Visitor};
+use serde::ser::SerializeMap;
+use serde::{Deserializer, Serializer};
+use serde_json::value::RawValue;
+
+use super::cow::{self, CowStr, OneOrMany};
+use super::process::{entries, Processor};
+use super::{Diagnostic, DiagnosticKind, Icon, Manifest};
+
+use std::borrow::Cow;
+use std::fmt;
+use std::marker::PhantomData;
+use std::path::Path;
+
+/// The accepted file types of a file handler: MIME types with their file
+/// extensions, in the order they were declared.
+type Accept<'a> = Vec<(Cow<'a, str>, Vec<Cow<'a, str>>)>;
+
+/// How an installed application is launched to open several files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LaunchType {
+  /// All files are opened in one window. This is the default.
+  #[serde(rename = "single-client")]
+  SingleClient,
+  /// Every file is opened in its own window.
+  #[serde(rename = "multiple-clients")]
+  MultipleClients,
+}
+
+/// Register an installed application as a handler for file types.
+///
+/// ## Example Output
+/// ```json
+/// "file_handlers": [{
+///   "action": "/open-csv",
+///   "accept": {
+///     "text/csv": [".csv"],
+///     "application/x-ourdoc": ".ourdoc"
+///   },
+///   "icons": [{
+///     "src": "csv-icon.png",
+///     "sizes": "256x256",
+///     "type": "image/png"
+///   }],
+///   "launch_type": "single-client"
+/// }]
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileHandler<'a> {
+  #[serde(borrow)]
+  action: Cow<'a, str>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  #[serde(borrow, default, deserialize_with = "cow::option")]
+  name: Option<Cow<'a, str>>,
+  #[serde(default)]
+  #[serde(serialize_with = "serialize_accept")]
+  #[serde(deserialize_with = "deserialize_accept")]
+  accept: Accept<'a>,
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  #[serde(borrow, default)]
+  icons: Vec<Icon<'a>>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  launch_type: Option<LaunchType>,
+}
+
+impl<'a> FileHandler<'a> {
+  /// Create a new `FileHandler` instance for the URL `action`.
+  ///
+  /// ## Example
+  /// ```rust
+  /// # extern crate webmanifest;
+  /// # use webmanifest::{FileHandler, LaunchType};
+  /// let handler = FileHandler::new("/open-csv")
+  ///   .accept("text/csv", [".csv"])
+  ///   .launch_type(LaunchType::MultipleClients);
+  /// ```
+  #[must_use]
+  #[inline]
+  pub fn new(action: impl Into<Cow<'a, str>>) -> Self {
+    Self {
+      action: action.into(),
+      name: None,
+      accept: vec![],
+      icons: vec![],
+      launch_type: None,
+    }
+  }
+
+  /// Set the `name` value, shown for the file type in the operating system.
+  #[must_use]
+  #[inline]
+  pub fn name(mut self, name: impl Into<Cow<'a, str>>) -> Self {
+    self.name = Some(name.into());
+    self
+  }
+
+  /// Accept a MIME type with the given file extensions. Extensions include
+  /// the leading dot, e.g. `.csv`, and can be strings or string slices.
+  ///
+  /// ## Example
+  /// ```rust
+  /// # extern crate webmanifest;
+  /// # use webmanifest::FileHandler;
+  /// let extensions: Vec<String> = vec![".md".into(), ".markdown".into()];
+  /// let handler = FileHandler::new("/open")
+  ///   .accept("text/markdown", extensions)
+  ///   .accept("image/*", None::<&str>);
+  /// assert!(handler.handles("README.markdown"));
+  /// ```
+  #[must_use]
+  pub fn accept(
+    mut self,
+    mime_type: impl Into<Cow<'a, str>>,
+    extensions: impl IntoIterator<Item = impl Into<Cow<'a, str>>>,
+  ) -> Self {
+    let extensions = extensions.into_iter().map(Into::into);
+    self.accept.push((mime_type.into(), extensions.collect()));
+    self
+  }
+
+  /// Add an `Icon` to the icons vector. Accepts an `Icon` or a reference to
+  /// one.
+  #[must_use]
+  #[inline]
+  pub fn icon(mut self, icon: impl Into<Icon<'a>>) -> Self {
+    self.icons.push(icon.into());
+    self
+  }
+
+  /// Set the `launch_type` value.
+  #[must_use]
+  #[inline]
+  pub fn launch_type(mut self, launch_type: LaunchType) -> Self {
+    self.launch_type = Some(launch_type);
+    self
+  }
+
+  /// Convert into a file handler that owns all of its strings.
+  #[must_use]
+  pub fn into_owned(self) -> FileHandler<'static> {
+    let accept = self.accept.into_iter().map(|(mime_type, extensions)| {
+      let extensions = extensions.into_iter().map(cow::owned).collect();
+      (cow::owned(mime_type), extensions)
+    });
+    FileHandler {
+      action: cow::owned(self.action),
+      name: cow::owned_option(self.name),
+      accept: accept.collect(),
+      icons: self.icons.into_iter().map(Icon::into_owned).collect(),
+      launch_type: self.launch_type,
+    }
+  }
+
+  /// Get the `action` value.
+  #[must_use]
+  #[inline]
+  pub fn get_action(&self) -> &str {
+    &self.action
+  }
+
+  /// Get the `name` value.
+  #[must_use]
+  #[inline]
+  pub fn get_name(&self) -> Option<&str> {
+    self.name.as_deref()
+  }
+
+  /// Get the `accept` value: every MIME type with its file extensions.
+  pub fn get_accept(&self) -> impl Iterator<Item = (&str, Vec<&str>)> {
+    self.accept.iter().map(|(mime_type, extensions)| {
+      let extensions = extensions.iter().map(|ext| ext.as_ref()).collect();
+      (mime_type.as_ref(), extensions)
+    })
+  }
+
+  /// Get the `icons` value.
+  #[must_use]
+  #[inline]
+  pub fn get_icons(&self) -> &[Icon<'a>] {
+    &self.icons
+  }
+
+  /// Get the `launch_type` value. Defaults to `LaunchType::SingleClient` if
+  /// not set.
+  #[must_use]
+  #[inline]
+  pub fn get_launch_type(&self) -> LaunchType {
+    self.launch_type.unwrap_or(LaunchType::SingleClient)
+  }
+
+  /// Check if the handler opens the file at `path`: its extension is
+  /// declared, or the MIME type guessed from the extension is accepted.
+  /// Both are matched case-insensitively, and `type/*` accepts every subtype.
+  ///
+  /// ## Example
+  /// ```rust
+  /// # extern crate webmanifest;
+  /// # use webmanifest::FileHandler;
+  /// let handler = FileHandler::new("/open").accept("image/*", None::<&str>);
+  /// assert!(handler.handles("photo.JPG"));
+  /// assert!(!handler.handles("data.csv"));
+  /// ```
+  #[must_use]
+  pub fn handles(&self, path: impl AsRef<Path>) -> bool {
+    let path = path.as_ref();
+    let extension = match path.extension().and_then(|ext| ext.to_str()) {
+      Some(extension) => format!(".{}", extension),
+      None => return false,
+    };
+    let guessed = mime_guess::from_path(path).first();
+    self.accept.iter().any(|(mime_type, extensions)| {
+      let by_extension = extensions
+        .iter()
+        .any(|declared| declared.trim().eq_ignore_ascii_case(&extension));
+      let by_type = guessed.as_ref().is_some_and(|guessed| {
+        let mime_type = mime_type.trim();
+        match mime_type.strip_suffix("/*") {
+          Some(kind) => guessed.type_().as_str().eq_ignore_ascii_case(kind),
+          None => guessed.essence_str().eq_ignore_ascii_case(mime_type),
+        }
+      });
+      by_extension || by_type
+    })
+  }
+
+  /// Process an entry of the `file_handlers` member.
+  pub(crate) fn process(
+    raw: &'a RawValue,
+    path: &str,
+    diagnostics: &mut Vec<Diagnostic>,
+  ) -> Option<Self> {
+    let mut processor = Processor::object(raw, path, diagnostics)?;
+    let action = processor.required_text("action");
+    let name = processor.text("name");
+    let accept = processor.nested("accept", process_accept);
+    let icons = processor.list("icons", Icon::process);
+    let launch_type = processor.keyword("launch_type");
+    processor.finish(diagnostics);
+    Some(Self {
+      action: action?,
+      name,
+      accept: accept.unwrap_or_default(),
+      icons,
+      launch_type,
+    })
+  }
+}
+
+impl<'a, 'b> From<&'b FileHandler<'a>> for FileHandler<'a> {
+  #[inline]
+  fn from(handler: &'b FileHandler<'a>) -> Self {
+    handler.clone()
+  }
+}
+
+impl<'a> Manifest<'a> {
+  /// Find the file handler that opens the file at `path`: the first one for
+  /// which `FileHandler::handles` is true.
+  ///
+  /// ## Example
+  /// ```rust
+  /// # extern crate webmanifest;
+  /// # use webmanifest::{FileHandler, Manifest};
+  /// let manifest = Manifest::builder("My Cool Application")
+  ///   .file_handler(FileHandler::new("/open-csv").accept("text/csv", [".csv"]))
+  ///   .file_handler(
+  ///     FileHandler::new("/open-doc").accept("application/x-ourdoc", [".ourdoc"]),
+  ///   );
+  /// let handler = manifest.file_handler_for("reports/2024.ourdoc");
+  /// assert_eq!(handler.map(|handler| handler.get_action()), Some("/open-doc"));
+  /// assert!(manifest.file_handler_for("notes.txt").is_none());
+  /// ```
+  #[must_use]
+  pub fn file_handler_for(
+    &self,
+    path: impl AsRef<Path>,
+  ) -> Option<&FileHandler<'a>> {
+    let path = path.as_ref();
+    self
+      .file_handlers
+      .iter()
+      .find(|handler| handler.handles(path))
+  }
+}
+
+/// Process the `accept` member of a file handler, skipping entries that are
+/// not a string or an array of strings.
+fn process_accept<'a>(
+  raw: &'a RawValue,
+  path: &str,
+  diagnostics: &mut Vec<Diagnostic>,
+) -> Option<Accept<'a>> {
+  let mut accept = vec![];
+  for (mime_type, raw) in entries(raw, path, diagnostics)? {
+    match serde_json::from_str::<OneOrMany<CowStr<'a>>>(raw.get()) {
+      Ok(extensions) => {
+        let extensions = extensions.into_vec().into_iter().map(|ext| ext.0);
+        accept.push((mime_type, extensions.collect()));
+      }
+      Err(_) => {
+        let kind = DiagnosticKind::InvalidType {
+          expected: "a string or an array of strings",
+        };
+        let member = format!("{}.{}", path, mime_type);
+        diagnostics.push(Diagnostic::warning(member, kind));
+      }
+    }
+  }
+  Some(accept)
+}
+
+/// Serialize the `accept` member as an object.
+fn serialize_accept<S: Serializer>(
+  accept: &Accept,
+  serializer: S,
+) -> Result<S::Ok, S::Error> {
+  let mut map = serializer.serialize_map(Some(accept.len()))?;
+  for (mime_type, extensions) in accept {
+    map.serialize_entry(mime_type, extensions)?;
+  }
+  map.end()
+}
+
+/// Deserialize the `accept` member, keeping the declared order.
+fn deserialize_accept<'de, D>(deserializer: D) -> Result<Accept<'de>, D::Error>
+where
+  D: Deserializer<'de>,
+{
+  struct AcceptVisitor<'de>(PhantomData<&'de ()>);
+
+  impl<'de> Visitor<'de> for AcceptVisitor<'de> {
+    type Value = Accept<'de>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+      f.write_str("a map of MIME types to file extensions")
+    }
+
+    fn visit_map<M: MapAccess<'de>>(
+      self,
+      mut map: M,
+    ) -> Result<Self::Value, M::Error> {
+      let mut accept = vec![];
+      while let Some((mime_type, extensions)) =
+        map.next_entry::<CowStr<'de>, OneOrMany<CowStr<'de>>>()?
+      {
+        let extensions = extensions.into_vec().into_iter().map(|ext| ext.0);
+        accept.push((mime_type.0, extensions.collect()));
+      }
+      Ok(accept)
+    }
+  }
+
+  deserializer.deserialize_map(AcceptVisitor(PhantomData))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn handles_wildcard_types() {
+    let handler = FileHandler::new("/open").accept("IMAGE/*", None::<&str>);
+    assert!(handler.handles("photo.jpg"));
+    assert!(handler.handles("photo.PNG"));
+    assert!(handler.handles("dir/drawing.svg"));
+    assert!(!handler.handles("data.csv"));
+    assert!(!handler.handles("image"));
+    // `image/*` only matches whole types, not prefixes of them.
+    let handler = FileHandler::new("/open").accept("im/*", None::<&str>);
+    assert!(!handler.handles("photo.jpg"));
+  }
+
+  #[test]
+  fn handles_declared_extensions() {
+    let handler =
+      FileHandler::new("/open").accept("application/x-ourdoc", [".OurDoc"]);
+    assert!(handler.handles("report.ourdoc"));
+    assert!(handler.handles("REPORT.OURDOC"));
+    assert!(!handler.handles("report.ourdoc.bak"));
+    assert!(!handler.handles("ourdoc"));
+    assert!(!handler.handles(".ourdoc"));
+  }
+
+  #[test]
+  fn handles_exact_types() {
+    let handler = FileHandler::new("/open").accept("text/csv", None::<&str>);
+    assert!(handler.handles("data.csv"));
+    assert!(!handler.handles("notes.txt"));
+  }
+
+  #[test]
+  fn first_matching_handler_wins() {
+    let manifest = Manifest::builder("App")
+      .file_handler(FileHandler::new("/images").accept("image/*", [".png"]))
+      .file_handler(FileHandler::new("/png").accept("image/png", [".png"]));
+    let handler = manifest.file_handler_for("icon.png").unwrap();
+    assert_eq!(handler.get_action(), "/images");
+  }
+
+  #[test]
+  fn accept_round_trips() {
+    let json = r#"{
+      "name": "App",
+      "file_handlers": [{
+        "action": "/open",
+        "accept": { "text/csv": ".csv", "image/*": [".png", ".gif"] }
+      }]
+    }"#;
+    let manifest = Manifest::parse(json).unwrap();
+    let accept: Vec<_> = manifest.get_file_handlers()[0].get_accept().collect();
+    assert_eq!(
+      accept,
+      [
+        ("text/csv", vec![".csv"]),
+        ("image/*", vec![".png", ".gif"])
+      ]
+    );
+    let json = manifest.build().unwrap();
+    assert!(json.contains(r#""text/csv":[".csv"]"#), "{}", json);
+  }
+}
@@ -0,0 +1,25 @@
+/// The form factor a screenshot is intended for.
+///
+/// ## Example
+/// ```rust
+/// # extern crate webmanifest;
+/// # use webmanifest::{FormFactor, Manifest, Screenshot};
+/// # fn main() -> Result<(), webmanifest::Error> {
+/// let name = "My Cool Application";
+/// let manifest = Manifest::builder(name)
+///   .screenshot(
+///     Screenshot::new("screenshots/desktop.png", (1280, 720))
+///       .form_factor(FormFactor::Wide),
+///   )
+///   .build()?;
+/// # Ok(())}
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FormFactor {
+  /// The screenshot is shown on wide screens, such as desktops.
+  #[serde(rename = "wide")]
+  Wide,
+  /// The screenshot is shown on narrow screens, such as phones.
+  #[serde(rename = "narrow")]
+  Narrow,
+}
@@ -0,0 +1,303 @@
+use image::imageops::{self, FilterType};
+use image::{DynamicImage, Rgba, RgbaImage};
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::{Diagnostic, DiagnosticKind, Error, Icon, Purpose, Purposes};
+
+/// The diameter of the maskable safe zone, as a fraction of the icon size.
+pub(crate) const SAFE_ZONE: f64 = 0.8;
+
+/// Generate a set of PNG icons from one high-resolution source image.
+///
+/// Requires the `image` feature.
+///
+/// Every size is written once per purpose:
+/// - `Purpose::Any`: the source scaled to fit the icon, centered on a
+///   transparent background. Written as `icon-{size}.png`.
+/// - `Purpose::Maskable`: the source scaled to fit inside the safe zone (the
+///   central circle with a diameter of 80% of the icon), on an opaque
+///   background. Written as `icon-maskable-{size}.png`.
+/// - `Purpose::Monochrome`: the alpha channel of the `Purpose::Any` variant,
+///   filled with black. Written as `icon-monochrome-{size}.png`.
+///
+/// ## Example
+/// ```rust,no_run
+/// # extern crate webmanifest;
+/// # use webmanifest::{IconGenerator, Manifest, Purpose};
+/// # fn main() -> Result<(), webmanifest::Error> {
+/// let icons = IconGenerator::new("assets/logo.png")
+///   .sizes(&[48, 72, 96, 144, 192, 512])
+///   .purpose(Purpose::Any)
+///   .purpose(Purpose::Maskable)
+///   .src_prefix("/icons/")
+///   .generate("public/icons")?;
+/// let manifest = icons
+///   .into_iter()
+///   .fold(Manifest::builder("My Cool Application"), Manifest::icon);
+/// # Ok(())}
+/// ```
+#[derive(Debug, Clone)]
+pub struct IconGenerator {
+  source: PathBuf,
+  sizes: Vec<u32>,
+  purposes: Purposes,
+  background: [u8; 4],
+  src_prefix: String,
+}
+
+impl IconGenerator {
+  /// Create a new instance for the image at `source`.
+  #[must_use]
+  #[inline]
+  pub fn new(source: impl AsRef<Path>) -> Self {
+    Self {
+      source: source.as_ref().to_path_buf(),
+      sizes: vec![],
+      purposes: Purposes::new(),
+      background: [255, 255, 255, 255],
+      src_prefix: String::new(),
+    }
+  }
+
+  /// Add a target size in pixels. `IconGenerator::generate` fails if a size
+  /// is 0.
+  #[must_use]
+  #[inline]
+  pub fn size(mut self, size: u32) -> Self {
+    if !self.sizes.contains(&size) {
+      self.sizes.push(size);
+    }
+    self
+  }
+
+  /// Add several target sizes in pixels.
+  #[must_use]
+  #[inline]
+  pub fn sizes(self, sizes: &[u32]) -> Self {
+    sizes
+      .iter()
+      .fold(self, |generator, size| generator.size(*size))
+  }
+
+  /// Add a purpose to generate icons for. Defaults to `Purpose::Any` if none
+  /// is set.
+  #[must_use]
+  #[inline]
+  pub fn purpose(mut self, purpose: Purpose) -> Self {
+    self.purposes = self.purposes.with(purpose);
+    self
+  }
+
+  /// Set the RGBA background color of maskable icons. Maskable icons must be
+  /// opaque, so the alpha channel is ignored. Defaults to white.
+  #[must_use]
+  #[inline]
+  pub fn background(mut self, rgba: [u8; 4]) -> Self {
+    self.background = [rgba[0], rgba[1], rgba[2], 255];
+    self
+  }
+
+  /// Set the prefix of the `src` of the generated icons, e.g. `/icons/`.
+  #[must_use]
+  #[inline]
+  pub fn src_prefix(mut self, prefix: impl Into<String>) -> Self {
+    self.src_prefix = prefix.into();
+    self
+  }
+
+  /// Write the icons into `out_dir`, creating it if needed, and return the
+  /// matching `Icon` entries.
+  ///
+  /// ## Errors
+  /// Fails if a size is 0, if the source can't be decoded, or if a file
+  /// can't be written.
+  pub fn generate(
+    &self,
+    out_dir: impl AsRef<Path>,
+  ) -> Result<Vec<Icon<'static>>, Error> {
+    if self.sizes.contains(&0) {
+      let kind = DiagnosticKind::InvalidValue {
+        value: "0".to_string(),
+      };
+      return Err(Error::InvalidValue(Diagnostic::error("sizes", kind)));
+    }
+    let out_dir = out_dir.as_ref();
+    fs::create_dir_all(out_dir)?;
+    let source = image::open(&self.source).map_err(Error::Image)?;
+    let purposes = if self.purposes.is_empty() {
+      Purpose::Any.into()
+    } else {
+      self.purposes
+    };
+
+    let mut icons = vec![];
+    for &size in &self.sizes {
+      for purpose in purposes.iter() {
+        let (name, image) = match purpose {
+          Purpose::Any => (format!("icon-{}.png", size), fit(&source, size)),
+          Purpose::Maskable => (
+            format!("icon-maskable-{}.png", size),
+            maskable(&source, size, self.background),
+          ),
+          Purpose::Monochrome => (
+            format!("icon-monochrome-{}.png", size),
+            monochrome(fit(&source, size)),
+          ),
+        };
+        image.save(out_dir.join(&name)).map_err(Error::Image)?;
+        let src = format!("{}{}", self.src_prefix, name);
+        icons.push(Icon::new(src, (size, size)).purpose(purpose));
+      }
+    }
+    Ok(icons)
+  }
+}
+
+/// Scale `source` to fit a `size` square, centered on a transparent
+/// background.
+fn fit(source: &DynamicImage, size: u32) -> RgbaImage {
+  let canvas = RgbaImage::from_pixel(size, size, Rgba([0, 0, 0, 0]));
+  place(canvas, source, size)
+}
+
+/// Scale `source` to fit inside the safe zone of a `size` square, centered on
+/// an opaque background.
+fn maskable(
+  source: &DynamicImage,
+  size: u32,
+  background: [u8; 4],
+) -> RgbaImage {
+  // The largest square that fits in the safe zone circle.
+  let inner = (f64::from(size) * SAFE_ZONE / 2f64.sqrt()).floor() as u32;
+  let canvas = RgbaImage::from_pixel(size, size, Rgba(background));
+  place(canvas, source, inner.max(1))
+}
+
+/// Scale `source` to fit an `inner` square and draw it centered on `canvas`.
+fn place(
+  mut canvas: RgbaImage,
+  source: &DynamicImage,
+  inner: u32,
+) -> RgbaImage {
+  let scaled = source.resize(inner, inner, FilterType::Lanczos3).to_rgba8();
+  let x = (canvas.width() - scaled.width()) / 2;
+  let y = (canvas.height() - scaled.height()) / 2;
+  imageops::overlay(&mut canvas, &scaled, i64::from(x), i64::from(y));
+  canvas
+}
+
+/// Keep only the alpha channel, filled with black.
+fn monochrome(mut image: RgbaImage) -> RgbaImage {
+  for pixel in image.pixels_mut() {
+    let alpha = pixel[3];
+    *pixel = Rgba([0, 0, 0, alpha]);
+  }
+  image
+}
+
+#[cfg(test)]
+mod tests {
+  use super::super::test_util::temp_dir;
+  use super::*;
+
+  /// Write a 64x32 red source image with a transparent background.
+  fn source(dir: &Path) -> PathBuf {
+    let path = dir.join("source.png");
+    let mut image = RgbaImage::from_pixel(64, 32, Rgba([0, 0, 0, 0]));
+    for x in 16..48 {
+      for y in 0..32 {
+        image.put_pixel(x, y, Rgba([255, 0, 0, 255]));
+      }
+    }
+    image.save(&path).unwrap();
+    path
+  }
+
+  #[test]
+  fn generates_every_size_and_purpose() {
+    let dir = temp_dir("generate");
+    let icons = IconGenerator::new(source(&dir))
+      .sizes(&[16, 48])
+      .purpose(Purpose::Any)
+      .purpose(Purpose::Maskable)
+      .purpose(Purpose::Monochrome)
+      .background([0, 0, 255, 0])
+      .src_prefix("/icons/")
+      .generate(dir.join("out"))
+      .unwrap();
+
+    let srcs: Vec<_> = icons.iter().map(|icon| icon.get_src()).collect();
+    assert_eq!(
+      srcs,
+      [
+        "/icons/icon-16.png",
+        "/icons/icon-maskable-16.png",
+        "/icons/icon-monochrome-16.png",
+        "/icons/icon-48.png",
+        "/icons/icon-maskable-48.png",
+        "/icons/icon-monochrome-48.png",
+      ]
+    );
+    assert_eq!(icons[1].get_purposes(), Purposes::from(Purpose::Maskable));
+
+    let open =
+      |name: &str| image::open(dir.join("out").join(name)).unwrap().to_rgba8();
+    let any = open("icon-48.png");
+    assert_eq!(any.dimensions(), (48, 48));
+    assert_eq!(any.get_pixel(0, 0), &Rgba([0, 0, 0, 0]));
+    assert_eq!(any.get_pixel(24, 24), &Rgba([255, 0, 0, 255]));
+
+    let maskable = open("icon-maskable-48.png");
+    assert_eq!(maskable.dimensions(), (48, 48));
+    assert_eq!(maskable.get_pixel(0, 0), &Rgba([0, 0, 255, 255]));
+    assert_eq!(maskable.get_pixel(24, 24), &Rgba([255, 0, 0, 255]));
+    assert_eq!(maskable.get_pixel(24, 4), &Rgba([0, 0, 255, 255]));
+
+    let monochrome = open("icon-monochrome-16.png");
+    assert_eq!(monochrome.dimensions(), (16, 16));
+    assert_eq!(monochrome.get_pixel(0, 0)[3], 0);
+    assert_eq!(monochrome.get_pixel(8, 8), &Rgba([0, 0, 0, 255]));
+    fs::remove_dir_all(dir).unwrap();
+  }
+
+  #[test]
+  fn defaults_to_any_purpose() {
+    let dir = temp_dir("generate-any");
+    let icons = IconGenerator::new(source(&dir))
+      .size(32)
+      .generate(&dir)
+      .unwrap();
+    assert_eq!(icons.len(), 1);
+    assert_eq!(icons[0].get_src(), "icon-32.png");
+    assert_eq!(icons[0].get_purposes(), Purposes::from(Purpose::Any));
+    fs::remove_dir_all(dir).unwrap();
+  }
+
+  #[test]
+  fn rejects_size_zero() {
+    let dir = temp_dir("generate-zero");
+    let result = IconGenerator::new(source(&dir))
+      .sizes(&[0, 16])
+      .generate(dir.join("out"));
+    match result {
+      Err(Error::InvalidValue(diagnostic)) => {
+        assert_eq!(diagnostic.get_member(), "sizes");
+      }
+      result => panic!("unexpected {:?}", result),
+    }
+    assert!(!dir.join("out").exists());
+    fs::remove_dir_all(dir).unwrap();
+  }
+
+  #[test]
+  fn fails_on_missing_source() {
+    let dir = temp_dir("generate-missing");
+    let result = IconGenerator::new(dir.join("missing.png"))
+      .size(16)
+      .generate(&dir);
+    assert!(matches!(result, Err(Error::Image(_))));
+    fs::remove_dir_all(dir).unwrap();
+  }
+}
@@ -1,4 +1,13 @@
 use mime_guess;
+use serde::de::{Error as DeError, Unexpected};
+use serde::{Deserialize, Deserializer};
+use serde_json::value::RawValue;
+
+use super::cow::{self, CowStr};
+use super::process::Processor;
+use super::{Diagnostic, DiagnosticKind, Error, Purpose, Purposes, Sizes};
+
+use std::borrow::Cow;
 
 /// Add an icon to the web manifest.
 ///
@@ -31,31 +40,256 @@ use mime_guess;
 /// }],
 /// ```
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Icon<'s> {
-  src: &'s str,
-  sizes: &'s str,
+pub struct Icon<'a> {
+  #[serde(borrow)]
+  src: Cow<'a, str>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  sizes: Option<Sizes>,
   #[serde(rename = "type")]
-  icon_type: String,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  #[serde(borrow, default, deserialize_with = "cow::option")]
+  icon_type: Option<Cow<'a, str>>,
+  #[serde(skip_serializing_if = "is_default_purpose")]
+  #[serde(default, deserialize_with = "deserialize_purpose")]
+  purpose: Option<Purposes>,
 }
 
-impl<'s> Icon<'s> {
+impl<'a> Icon<'a> {
   /// Create a new `Icon` instance.
   ///
+  /// `sizes` can be a `(width, height)` tuple, a `Size`, or a `Sizes` list.
+  ///
   /// ## Example
   /// ```rust
   /// # extern crate webmanifest;
-  /// # use webmanifest::Icon;
+  /// # use webmanifest::{Icon, Size, Sizes};
   /// let src = "images/touch/homescreen48.png";
-  /// let icon = Icon::new(&src, "48x48");
+  /// let icon = Icon::new(src, (48, 48));
+  ///
+  /// let src = "images/touch/homescreen.svg";
+  /// let sizes: Sizes = vec![Size::from((48, 48)), Size::Any].into_iter().collect();
+  /// let icon = Icon::new(src, sizes);
   /// ```
   #[must_use]
   #[inline]
-  pub fn new(src: &'s str, sizes: &'s str) -> Self {
-    let icon_type = mime_guess::guess_mime_type(&src).to_string();
+  pub fn new(src: impl Into<Cow<'a, str>>, sizes: impl Into<Sizes>) -> Self {
+    let src = src.into();
+    let icon_type = mime_guess::from_path(src.as_ref()).first_or_octet_stream();
     Self {
       src,
+      sizes: Some(sizes.into()).filter(|sizes| !sizes.is_empty()),
+      icon_type: Some(Cow::Owned(icon_type.to_string())),
+      purpose: None,
+    }
+  }
+
+  /// Create a new `Icon` instance from a `sizes` string such as
+  /// `"48x48 96x96"`.
+  ///
+  /// ## Errors
+  /// Fails if `sizes` does not follow the `sizes` syntax.
+  ///
+  /// ## Example
+  /// ```rust
+  /// # extern crate webmanifest;
+  /// # use webmanifest::Icon;
+  /// # fn main() -> Result<(), webmanifest::Error> {
+  /// let src = "images/touch/homescreen48.png";
+  /// let icon = Icon::try_new(src, "48x48")?;
+  /// assert!(Icon::try_new(src, "48*48").is_err());
+  /// # Ok(())}
+  /// ```
+  #[inline]
+  pub fn try_new(
+    src: impl Into<Cow<'a, str>>,
+    sizes: &str,
+  ) -> Result<Self, Error> {
+    Ok(Self::new(src, Sizes::parse(sizes)?))
+  }
+
+  /// Add to the `purpose` value. Can be called several times to declare one
+  /// asset for several purposes.
+  ///
+  /// ## Example
+  /// ```rust
+  /// # extern crate webmanifest;
+  /// # use webmanifest::{Icon, Purpose};
+  /// let src = "images/touch/homescreen192.png";
+  /// let icon = Icon::new(src, (192, 192))
+  ///   .purpose(Purpose::Any)
+  ///   .purpose(Purpose::Maskable);
+  /// assert_eq!(icon.get_purposes().to_string(), "any maskable");
+  /// ```
+  #[must_use]
+  #[inline]
+  pub fn purpose(mut self, purpose: impl Into<Purposes>) -> Self {
+    let purposes = self.purpose.unwrap_or_default();
+    let purposes = purpose.into().iter().fold(purposes, Purposes::with);
+    self.purpose = Some(purposes).filter(|purposes| !purposes.is_empty());
+    self
+  }
+
+  /// Convert into an icon that owns all of its strings.
+  #[must_use]
+  pub fn into_owned(self) -> Icon<'static> {
+    Icon {
+      src: cow::owned(self.src),
+      sizes: self.sizes,
+      icon_type: cow::owned_option(self.icon_type),
+      purpose: self.purpose,
+    }
+  }
+
+  /// Get the `src` value.
+  #[must_use]
+  #[inline]
+  pub fn get_src(&self) -> &str {
+    &self.src
+  }
+
+  /// Get the `sizes` value.
+  #[must_use]
+  #[inline]
+  pub fn get_sizes(&self) -> Option<&Sizes> {
+    self.sizes.as_ref()
+  }
+
+  /// Get the `type` value.
+  #[must_use]
+  #[inline]
+  pub fn get_icon_type(&self) -> Option<&str> {
+    self.icon_type.as_deref()
+  }
+
+  /// Get the `purpose` value. Defaults to `Purpose::Any` if not set or empty.
+  #[must_use]
+  #[inline]
+  pub fn get_purposes(&self) -> Purposes {
+    self.purpose.unwrap_or_else(|| Purpose::Any.into())
+  }
+
+  /// Process an entry of the `icons` member.
+  pub(crate) fn process(
+    raw: &'a RawValue,
+    path: &str,
+    diagnostics: &mut Vec<Diagnostic>,
+  ) -> Option<Self> {
+    let mut processor = Processor::object(raw, path, diagnostics)?;
+    let src = processor.required_text("src");
+    let sizes = processor.sizes("sizes");
+    let icon_type = processor.text("type");
+    let purpose = match processor.text("purpose") {
+      Some(value) => {
+        for keyword in value.split_whitespace() {
+          if Purpose::parse(keyword).is_none() {
+            let kind = DiagnosticKind::InvalidValue {
+              value: keyword.to_string(),
+            };
+            processor.warn("purpose", kind);
+          }
+        }
+        let purposes = Purposes::parse(&value);
+        if purposes.is_empty() {
+          // An icon without any usable purpose must not be used at all.
+          processor.warn("purpose", DiagnosticKind::NoUsablePurpose);
+          processor.finish(diagnostics);
+          return None;
+        }
+        Some(purposes)
+      }
+      None => None,
+    };
+    processor.finish(diagnostics);
+    Some(Self {
+      src: src?,
       sizes,
       icon_type,
+      purpose,
+    })
+  }
+}
+
+impl<'a, 'b> From<&'b Icon<'a>> for Icon<'a> {
+  #[inline]
+  fn from(icon: &'b Icon<'a>) -> Self {
+    icon.clone()
+  }
+}
+
+/// Check if the `purpose` member can be left out: it is unset or empty, both
+/// of which mean `any`.
+fn is_default_purpose(purpose: &Option<Purposes>) -> bool {
+  match purpose {
+    Some(purposes) => purposes.is_empty(),
+    None => true,
+  }
+}
+
+/// Deserialize the `purpose` member, failing if it has no known purpose.
+/// Such an icon must not be used, so it can't fall back to `any`.
+fn deserialize_purpose<'de, D>(
+  deserializer: D,
+) -> Result<Option<Purposes>, D::Error>
+where
+  D: Deserializer<'de>,
+{
+  let value = match Option::<CowStr>::deserialize(deserializer)? {
+    Some(value) => value.0,
+    None => return Ok(None),
+  };
+  let purposes = Purposes::parse(&value);
+  if purposes.is_empty() {
+    let unexpected = Unexpected::Str(&value);
+    let expected = &"at least one known purpose";
+    return Err(D::Error::invalid_value(unexpected, expected));
+  }
+  Ok(Some(purposes))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::super::Manifest;
+  use super::*;
+
+  #[test]
+  fn parse_rejects_icon_without_known_purpose() {
+    for purpose in &["badge", ""] {
+      let json = format!(
+        r#"{{ "name": "App", "icons": [{{ "src": "a.png", "purpose": "{}" }}] }}"#,
+        purpose
+      );
+      assert!(Manifest::parse(&json).is_err());
+      let (manifest, _) = Manifest::process(&json).unwrap();
+      assert!(manifest.get_icons().is_empty());
     }
+
+    let json = r#"{
+      "name": "App",
+      "icons": [{ "src": "a.png", "purpose": "badge maskable" }]
+    }"#;
+    let manifest = Manifest::parse(json).unwrap();
+    let icon = &manifest.get_icons()[0];
+    assert_eq!(icon.get_purposes(), Purposes::from(Purpose::Maskable));
+  }
+
+  #[test]
+  fn empty_purposes_are_not_serialized() {
+    let icon = Icon::new("a.png", (48, 48)).purpose(Purposes::new());
+    assert_eq!(icon.get_purposes(), Purposes::from(Purpose::Any));
+    let manifest = Manifest::builder("App").icon(icon);
+    assert!(!manifest.build().unwrap().contains("purpose"));
+  }
+
+  #[test]
+  fn process_reports_icon_without_purpose() {
+    let json = r#"{
+      "name": "App",
+      "icons": [{ "src": "a.png", "purpose": "" }]
+    }"#;
+    let (manifest, diagnostics) = Manifest::process(json).unwrap();
+    assert!(manifest.get_icons().is_empty());
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].get_member(), "icons[0].purpose");
+    assert_eq!(diagnostics[0].get_kind(), &DiagnosticKind::NoUsablePurpose);
   }
 }
@@ -0,0 +1,637 @@
+use std::borrow::Cow;
+use std::fmt;
+use std::str::FromStr;
+
+use super::{Diagnostic, DiagnosticKind, Direction, Error};
+
+/// A BCP 47 language tag, the value of `lang`.
+///
+/// Parses tags that are well-formed according to [RFC
+/// 5646](https://www.rfc-editor.org/rfc/rfc5646): a primary language with up
+/// to three extended languages, an optional script and region, variants,
+/// extensions and a private use part. Tags made of a private use part only,
+/// and the irregular grandfathered tags such as `i-klingon`, are accepted too.
+/// Subtags are not checked against the IANA registry, so `zz-ZZ` is accepted.
+///
+/// Subtags are case-insensitive. `LanguageTag::to_string` uses the
+/// conventional case: lowercase, except for title case scripts and uppercase
+/// regions.
+///
+/// ## Example
+/// ```rust
+/// # extern crate webmanifest;
+/// # use webmanifest::{Direction, LanguageTag};
+/// # fn main() -> Result<(), webmanifest::Error> {
+/// let tag: LanguageTag = "SR-latn-rs-U-ca-gregory".parse()?;
+/// assert_eq!(tag.to_string(), "sr-Latn-RS-u-ca-gregory");
+/// assert_eq!(tag.get_script(), Some("latn"));
+/// assert_eq!(tag.get_region(), Some("rs"));
+///
+/// let tag = LanguageTag::parse("iw-IL")?;
+/// assert_eq!(tag.canonicalize().to_string(), "he-IL");
+/// assert_eq!(tag.direction(), Some(Direction::Rtl));
+///
+/// assert!(LanguageTag::parse("en-US-").is_err());
+/// assert!(LanguageTag::parse("de-1996-1996").is_err());
+/// # Ok(())}
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LanguageTag {
+  language: String,
+  extlangs: Vec<String>,
+  script: Option<String>,
+  region: Option<String>,
+  variants: Vec<String>,
+  extensions: Vec<(char, Vec<String>)>,
+  private_use: Vec<String>,
+  grandfathered: bool,
+}
+
+impl LanguageTag {
+  /// Parse a language tag, failing if it is not well-formed.
+  pub fn parse(value: &str) -> Result<Self, Error> {
+    Self::parse_kind(value)
+      .map_err(|kind| Error::InvalidValue(Diagnostic::error("lang", kind)))
+  }
+
+  /// Parse a language tag, failing with the kind of diagnostic to report on
+  /// the member it came from.
+  pub(crate) fn parse_kind(value: &str) -> Result<Self, DiagnosticKind> {
+    parse_tag(value).map_err(|reason| DiagnosticKind::InvalidLanguageTag {
+      value: value.to_string(),
+      reason,
+    })
+  }
+
+  /// Get the primary language subtag, e.g. `zh` for `zh-yue-HK`. Empty for
+  /// tags that only have a private use part, and the whole tag for irregular
+  /// grandfathered tags.
+  #[must_use]
+  #[inline]
+  pub fn get_language(&self) -> &str {
+    &self.language
+  }
+
+  /// Get the extended language subtags, e.g. `["yue"]` for `zh-yue-HK`.
+  #[must_use]
+  #[inline]
+  pub fn get_extended_languages(&self) -> &[String] {
+    &self.extlangs
+  }
+
+  /// Get the script subtag, lowercased.
+  #[must_use]
+  #[inline]
+  pub fn get_script(&self) -> Option<&str> {
+    self.script.as_deref()
+  }
+
+  /// Get the region subtag, lowercased.
+  #[must_use]
+  #[inline]
+  pub fn get_region(&self) -> Option<&str> {
+    self.region.as_deref()
+  }
+
+  /// Get the variant subtags.
+  #[must_use]
+  #[inline]
+  pub fn get_variants(&self) -> &[String] {
+    &self.variants
+  }
+
+  /// Get the subtags of the extension introduced by `singleton`, e.g.
+  /// `["ca", "gregory"]` for `u` in `th-u-ca-gregory`.
+  #[must_use]
+  pub fn get_extension(&self, singleton: char) -> Option<&[String]> {
+    let singleton = singleton.to_ascii_lowercase();
+    self
+      .extensions
+      .iter()
+      .find(|(name, _)| *name == singleton)
+      .map(|(_, subtags)| subtags.as_slice())
+  }
+
+  /// Get the private use subtags that follow `x`.
+  #[must_use]
+  #[inline]
+  pub fn get_private_use(&self) -> &[String] {
+    &self.private_use
+  }
+
+  /// Check if this is one of the irregular grandfathered tags, such as
+  /// `i-klingon`, which don't follow the syntax of other tags.
+  #[must_use]
+  #[inline]
+  pub fn is_grandfathered(&self) -> bool {
+    self.grandfathered
+  }
+
+  /// Convert into the canonical form defined by RFC 5646: extensions are
+  /// sorted by their singleton, grandfathered tags and deprecated subtags are
+  /// replaced by their preferred values, and an extended language replaces
+  /// its primary language, e.g. `zh-yue` becomes `yue`.
+  ///
+  /// Only the deprecated subtags of common languages and regions are
+  /// replaced.
+  #[must_use]
+  pub fn canonicalize(&self) -> Self {
+    let mut tag = self.clone();
+    let key = self.to_string();
+    let irregular = IRREGULAR
+      .iter()
+      .find(|(name, _)| name.eq_ignore_ascii_case(&key))
+      .and_then(|(_, preferred)| *preferred);
+    let regular = REGULAR
+      .iter()
+      .find(|(name, _)| name.eq_ignore_ascii_case(&key))
+      .map(|(_, preferred)| *preferred);
+    let preferred = irregular.or(regular);
+    if let Some(preferred) = preferred {
+      if let Ok(preferred) = parse_tag(preferred) {
+        tag = preferred;
+      }
+    }
+    if tag.extlangs.len() == 1 {
+      tag.language = tag.extlangs.remove(0);
+    }
+    if let Some((_, preferred)) = DEPRECATED_LANGUAGES
+      .iter()
+      .find(|(old, _)| *old == tag.language)
+    {
+      tag.language = preferred.to_string();
+    }
+    if tag.script.as_deref() == Some("qaai") {
+      tag.script = Some("zinh".to_string());
+    }
+    if let Some(region) = tag.region.as_mut() {
+      if let Some((_, preferred)) =
+        DEPRECATED_REGIONS.iter().find(|(old, _)| old == region)
+      {
+        *region = preferred.to_string();
+      }
+    }
+    tag.extensions.sort_by_key(|(singleton, _)| *singleton);
+    tag
+  }
+
+  /// Get the direction the language is written in, judged by its script.
+  ///
+  /// The script is the script subtag, or else the default script of the
+  /// language in its region. Languages are assumed to be written in a
+  /// left-to-right script unless they are known to default to a right-to-left
+  /// one, such as Arabic, Hebrew, Persian or Urdu. Returns `None` if the
+  /// script can't be told: for undetermined, private use and grandfathered
+  /// tags, and for scripts that are not specific to a language, such as
+  /// `Zyyy`.
+  #[must_use]
+  pub fn direction(&self) -> Option<Direction> {
+    let tag = self.canonicalize();
+    if tag.grandfathered || tag.language.is_empty() {
+      return None;
+    }
+    let script = match tag.script.as_deref() {
+      Some(script) => Some(script),
+      None => default_script(&tag.language, tag.region.as_deref()),
+    };
+    match script {
+      Some(script) if script.starts_with('z') => None,
+      Some(script) => {
+        let script = title_case(script);
+        if RTL_SCRIPTS.binary_search(&script.as_str()).is_ok() {
+          Some(Direction::Rtl)
+        } else {
+          Some(Direction::Ltr)
+        }
+      }
+      None => {
+        let language = tag.language.as_str();
+        let private_use =
+          language.len() == 3 && ("qaa"..="qtz").contains(&language);
+        let unknown =
+          ["mis", "mul", "und", "zxx"].contains(&language) || private_use;
+        if unknown {
+          None
+        } else {
+          Some(Direction::Ltr)
+        }
+      }
+    }
+  }
+}
+
+impl FromStr for LanguageTag {
+  type Err = Error;
+
+  #[inline]
+  fn from_str(value: &str) -> Result<Self, Self::Err> {
+    Self::parse(value)
+  }
+}
+
+impl fmt::Display for LanguageTag {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    if self.grandfathered {
+      let name = IRREGULAR
+        .iter()
+        .map(|(name, _)| *name)
+        .find(|name| name.eq_ignore_ascii_case(&self.language));
+      return f.write_str(name.unwrap_or(&self.language));
+    }
+    let mut subtags: Vec<Cow<str>> = vec![];
+    if !self.language.is_empty() {
+      subtags.push(Cow::Borrowed(&self.language));
+    }
+    subtags.extend(
+      self
+        .extlangs
+        .iter()
+        .map(|extlang| Cow::Borrowed(&**extlang)),
+    );
+    if let Some(script) = &self.script {
+      subtags.push(Cow::Owned(title_case(script)));
+    }
+    if let Some(region) = &self.region {
+      subtags.push(Cow::Owned(region.to_ascii_uppercase()));
+    }
+    subtags.extend(
+      self
+        .variants
+        .iter()
+        .map(|variant| Cow::Borrowed(&**variant)),
+    );
+    for (singleton, extension) in &self.extensions {
+      subtags.push(Cow::Owned(singleton.to_string()));
+      subtags.extend(extension.iter().map(|subtag| Cow::Borrowed(&**subtag)));
+    }
+    if !self.private_use.is_empty() {
+      subtags.push(Cow::Borrowed("x"));
+      subtags.extend(
+        self
+          .private_use
+          .iter()
+          .map(|subtag| Cow::Borrowed(&**subtag)),
+      );
+    }
+    f.write_str(&subtags.join("-"))
+  }
+}
+
+impl<'a> From<LanguageTag> for Cow<'a, str> {
+  #[inline]
+  fn from(tag: LanguageTag) -> Self {
+    Cow::Owned(tag.to_string())
+  }
+}
+
+/// Parse a language tag, returning why it is not well-formed on failure.
+fn parse_tag(value: &str) -> Result<LanguageTag, &'static str> {
+  let lower = value.to_ascii_lowercase();
+  let mut tag = LanguageTag {
+    language: String::new(),
+    extlangs: vec![],
+    script: None,
+    region: None,
+    variants: vec![],
+    extensions: vec![],
+    private_use: vec![],
+    grandfathered: false,
+  };
+  let grandfathered = IRREGULAR
+    .iter()
+    .any(|(name, _)| name.eq_ignore_ascii_case(&lower));
+  if grandfathered {
+    tag.language = lower;
+    tag.grandfathered = true;
+    return Ok(tag);
+  }
+
+  let subtags: Vec<&str> = lower.split('-').collect();
+  for subtag in &subtags {
+    if subtag.is_empty() {
+      return Err("empty subtag");
+    }
+    if subtag.len() > 8 || !subtag.bytes().all(|b| b.is_ascii_alphanumeric()) {
+      return Err("subtags must be 1 to 8 letters or digits");
+    }
+  }
+  let is_alpha = |subtag: &str| subtag.bytes().all(|b| b.is_ascii_alphabetic());
+  let is_digit = |subtag: &str| subtag.bytes().all(|b| b.is_ascii_digit());
+
+  let mut index = 0;
+  if subtags[0] != "x" {
+    let language = subtags[0];
+    if language.len() < 2 || !is_alpha(language) {
+      return Err("the primary language must be 2 to 8 letters");
+    }
+    tag.language = language.to_string();
+    index = 1;
+
+    if language.len() <= 3 {
+      while let Some(subtag) = subtags.get(index) {
+        if tag.extlangs.len() == 3 || subtag.len() != 3 || !is_alpha(subtag) {
+          break;
+        }
+        tag.extlangs.push(subtag.to_string());
+        index += 1;
+      }
+    }
+    if let Some(subtag) = subtags.get(index) {
+      if subtag.len() == 4 && is_alpha(subtag) {
+        tag.script = Some(subtag.to_string());
+        index += 1;
+      }
+    }
+    if let Some(subtag) = subtags.get(index) {
+      let region = (subtag.len() == 2 && is_alpha(subtag))
+        || (subtag.len() == 3 && is_digit(subtag));
+      if region {
+        tag.region = Some(subtag.to_string());
+        index += 1;
+      }
+    }
+    while let Some(subtag) = subtags.get(index) {
+      let variant = subtag.len() >= 5
+        || (subtag.len() == 4 && subtag.as_bytes()[0].is_ascii_digit());
+      if !variant {
+        break;
+      }
+      if tag.variants.iter().any(|variant| variant == subtag) {
+        return Err("duplicate variant");
+      }
+      tag.variants.push(subtag.to_string());
+      index += 1;
+    }
+    while let Some(subtag) = subtags.get(index) {
+      if subtag.len() != 1 || *subtag == "x" {
+        break;
+      }
+      let singleton = subtag.as_bytes()[0] as char;
+      if tag.extensions.iter().any(|(name, _)| *name == singleton) {
+        return Err("duplicate extension");
+      }
+      index += 1;
+      let mut extension = vec![];
+      while let Some(subtag) = subtags.get(index) {
+        if subtag.len() == 1 {
+          break;
+        }
+        extension.push(subtag.to_string());
+        index += 1;
+      }
+      if extension.is_empty() {
+        return Err("extension without subtags");
+      }
+      tag.extensions.push((singleton, extension));
+    }
+  }
+
+  if subtags.get(index) == Some(&"x") {
+    tag.private_use =
+      subtags[index + 1..].iter().map(|s| s.to_string()).collect();
+    if tag.private_use.is_empty() {
+      return Err("private use part without subtags");
+    }
+    index = subtags.len();
+  }
+  if index < subtags.len() {
+    return Err("subtags are out of order");
+  }
+  Ok(tag)
+}
+
+/// Convert a lowercase script subtag to title case.
+fn title_case(script: &str) -> String {
+  let mut script = script.to_string();
+  script[..1].make_ascii_uppercase();
+  script
+}
+
+/// Get the lowercase default script of a language in `region`, if it is
+/// written right-to-left or differs from the usual script in that region.
+fn default_script(
+  language: &str,
+  region: Option<&str>,
+) -> Option<&'static str> {
+  let regional = REGIONAL_SCRIPTS.iter().find(|(name, name_region, _)| {
+    *name == language && Some(*name_region) == region
+  });
+  if let Some((_, _, script)) = regional {
+    return Some(script);
+  }
+  RTL_LANGUAGES
+    .binary_search_by_key(&language, |&(name, _)| name)
+    .ok()
+    .map(|index| RTL_LANGUAGES[index].1)
+}
+
+/// The irregular grandfathered tags, which don't follow the syntax of other
+/// tags, with their preferred values.
+const IRREGULAR: [(&str, Option<&str>); 17] = [
+  ("en-GB-oed", Some("en-GB-oxendict")),
+  ("i-ami", Some("ami")),
+  ("i-bnn", Some("bnn")),
+  ("i-default", None),
+  ("i-enochian", None),
+  ("i-hak", Some("hak")),
+  ("i-klingon", Some("tlh")),
+  ("i-lux", Some("lb")),
+  ("i-mingo", None),
+  ("i-navajo", Some("nv")),
+  ("i-pwn", Some("pwn")),
+  ("i-tao", Some("tao")),
+  ("i-tay", Some("tay")),
+  ("i-tsu", Some("tsu")),
+  ("sgn-BE-FR", Some("sfb")),
+  ("sgn-BE-NL", Some("vgt")),
+  ("sgn-CH-DE", Some("sgg")),
+];
+
+/// The regular grandfathered and redundant tags that have a preferred value.
+const REGULAR: [(&str, &str); 7] = [
+  ("art-lojban", "jbo"),
+  ("no-bok", "nb"),
+  ("no-nyn", "nn"),
+  ("zh-guoyu", "cmn"),
+  ("zh-hakka", "hak"),
+  ("zh-min-nan", "nan"),
+  ("zh-xiang", "hsn"),
+];
+
+/// Deprecated language subtags and their preferred values.
+const DEPRECATED_LANGUAGES: [(&str, &str); 5] = [
+  ("in", "id"),
+  ("iw", "he"),
+  ("ji", "yi"),
+  ("jw", "jv"),
+  ("mo", "ro"),
+];
+
+/// Deprecated region subtags and their preferred values.
+const DEPRECATED_REGIONS: [(&str, &str); 6] = [
+  ("bu", "mm"),
+  ("dd", "de"),
+  ("fx", "fr"),
+  ("tp", "tl"),
+  ("yd", "ye"),
+  ("zr", "cd"),
+];
+
+/// Languages whose default script is written right-to-left, sorted by
+/// language.
+const RTL_LANGUAGES: [(&str, &str); 25] = [
+  ("ar", "arab"),
+  ("arc", "armi"),
+  ("azb", "arab"),
+  ("bal", "arab"),
+  ("bqi", "arab"),
+  ("ckb", "arab"),
+  ("dv", "thaa"),
+  ("fa", "arab"),
+  ("glk", "arab"),
+  ("he", "hebr"),
+  ("khw", "arab"),
+  ("ks", "arab"),
+  ("lrc", "arab"),
+  ("mzn", "arab"),
+  ("nqo", "nkoo"),
+  ("pnb", "arab"),
+  ("prs", "arab"),
+  ("ps", "arab"),
+  ("sd", "arab"),
+  ("sdh", "arab"),
+  ("skr", "arab"),
+  ("syr", "syrc"),
+  ("ug", "arab"),
+  ("ur", "arab"),
+  ("yi", "hebr"),
+];
+
+/// Languages whose default script depends on the region.
+const REGIONAL_SCRIPTS: [(&str, &str, &str); 5] = [
+  ("az", "ir", "arab"),
+  ("kk", "cn", "arab"),
+  ("pa", "pk", "arab"),
+  ("sd", "in", "deva"),
+  ("uz", "af", "arab"),
+];
+
+/// The scripts written right-to-left, sorted.
+const RTL_SCRIPTS: [&str; 39] = [
+  "Adlm", "Arab", "Aran", "Armi", "Avst", "Chrs", "Cprt", "Elym", "Hatr",
+  "Hebr", "Hung", "Khar", "Lydi", "Mand", "Mani", "Mend", "Merc", "Mero",
+  "Narb", "Nbat", "Nkoo", "Orkh", "Ougr", "Palm", "Phli", "Phlp", "Phnx",
+  "Prti", "Rohg", "Samr", "Sarb", "Sogd", "Sogo", "Syrc", "Syre", "Syrj",
+  "Syrn", "Thaa", "Yezi",
+];
+
+#[cfg(test)]
+mod tests {
+  use super::super::{Manifest, Severity, Url};
+  use super::*;
+
+  fn canonical(value: &str) -> String {
+    LanguageTag::parse(value)
+      .unwrap()
+      .canonicalize()
+      .to_string()
+  }
+
+  #[test]
+  fn at_most_three_extended_languages() {
+    let tag = LanguageTag::parse("zh-aaa-bbb-ccc").unwrap();
+    assert_eq!(tag.get_extended_languages(), ["aaa", "bbb", "ccc"]);
+    assert!(LanguageTag::parse("zh-aaa-bbb-ccc-ddd").is_err());
+    // Only languages of 2 or 3 letters have extended languages.
+    assert!(LanguageTag::parse("abcd-aaa").is_err());
+  }
+
+  #[test]
+  fn extensions_need_subtags() {
+    let tag = LanguageTag::parse("en-a-bbb-U-ca-gregory").unwrap();
+    assert_eq!(tag.get_extension('a').unwrap(), ["bbb"]);
+    assert_eq!(tag.get_extension('U').unwrap(), ["ca", "gregory"]);
+    for value in &["en-u", "en-u-x-foo", "en-u-a-bbb", "en-a-bbb-a-ccc"] {
+      assert!(LanguageTag::parse(value).is_err(), "{}", value);
+    }
+  }
+
+  #[test]
+  fn private_use_only() {
+    let tag = LanguageTag::parse("X-Private-Use").unwrap();
+    assert_eq!(tag.get_language(), "");
+    assert_eq!(tag.get_private_use(), ["private", "use"]);
+    assert_eq!(tag.to_string(), "x-private-use");
+    assert_eq!(tag.direction(), None);
+    assert!(LanguageTag::parse("x").is_err());
+    assert!(LanguageTag::parse("x-toolongsubtag").is_err());
+  }
+
+  #[test]
+  fn grandfathered_tags_canonicalize() {
+    assert_eq!(canonical("zh-min-nan"), "nan");
+    assert_eq!(canonical("art-lojban"), "jbo");
+    assert_eq!(canonical("ZH-Hakka"), "hak");
+    assert_eq!(canonical("i-klingon"), "tlh");
+    assert_eq!(canonical("i-default"), "i-default");
+    assert_eq!(canonical("zh-yue-HK"), "yue-HK");
+    assert_eq!(canonical("de-DD-u-ca-a-bbb-x-y"), "de-DE-a-bbb-u-ca-x-y");
+  }
+
+  #[test]
+  fn direction_depends_on_region() {
+    let direction = |value| LanguageTag::parse(value).unwrap().direction();
+    assert_eq!(direction("pa"), Some(Direction::Ltr));
+    assert_eq!(direction("pa-PK"), Some(Direction::Rtl));
+    assert_eq!(direction("pa-Guru-PK"), Some(Direction::Ltr));
+    assert_eq!(direction("sd"), Some(Direction::Rtl));
+    assert_eq!(direction("sd-IN"), Some(Direction::Ltr));
+    assert_eq!(direction("az-Arab"), Some(Direction::Rtl));
+    assert_eq!(direction("ar-Zyyy"), None);
+  }
+
+  #[test]
+  fn private_use_languages_have_no_direction() {
+    let direction = |value| LanguageTag::parse(value).unwrap().direction();
+    assert_eq!(direction("qaa"), None);
+    assert_eq!(direction("qtz"), None);
+    assert_eq!(direction("und"), None);
+    assert_eq!(direction("qb"), Some(Direction::Ltr));
+    assert_eq!(direction("qu"), Some(Direction::Ltr));
+    assert_eq!(direction("qtzz"), Some(Direction::Ltr));
+  }
+
+  #[test]
+  fn validate_reports_conflicting_direction() {
+    let base_url = Url::parse("https://e.com/manifest.json").unwrap();
+    let conflicts = |lang, dir| {
+      let manifest = Manifest::builder("App").lang(lang).direction(dir);
+      manifest
+        .validate(&base_url)
+        .into_iter()
+        .filter(|d| d.get_member() == "dir")
+        .collect::<Vec<_>>()
+    };
+
+    let diagnostics = conflicts("ar", Direction::Ltr);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].get_severity(), Severity::Warning);
+    let kind = DiagnosticKind::ConflictingDirection {
+      lang: "ar".to_string(),
+      expected: Direction::Rtl,
+    };
+    assert_eq!(diagnostics[0].get_kind(), &kind);
+
+    let diagnostics = conflicts("en", Direction::Rtl);
+    let kind = DiagnosticKind::ConflictingDirection {
+      lang: "en".to_string(),
+      expected: Direction::Ltr,
+    };
+    assert_eq!(diagnostics[0].get_kind(), &kind);
+
+    assert!(conflicts("ar", Direction::Auto).is_empty());
+    assert!(conflicts("en", Direction::Auto).is_empty());
+    assert!(conflicts("ar", Direction::Rtl).is_empty());
+    assert!(conflicts("x-foo", Direction::Rtl).is_empty());
+  }
+}
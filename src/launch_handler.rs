@@ -0,0 +1,231 @@
+use serde::{Serialize, Serializer};
+use serde_json::value::RawValue;
+
+use super::cow;
+use super::process::{keyword, Processor};
+use super::Diagnostic;
+
+/// How a launch of the application is handled if a window of it is already
+/// open.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ClientMode {
+  /// The user agent picks the behavior for the platform. This is the default
+  /// and is always supported.
+  #[serde(rename = "auto")]
+  Auto,
+  /// A new window is opened for the launch URL.
+  #[serde(rename = "navigate-new")]
+  NavigateNew,
+  /// The most recently used window is brought to the front and navigated to
+  /// the launch URL.
+  #[serde(rename = "navigate-existing")]
+  NavigateExisting,
+  /// The most recently used window is brought to the front without
+  /// navigating it. The launch URL is passed to the `launchQueue` instead.
+  #[serde(rename = "focus-existing")]
+  FocusExisting,
+}
+
+impl ClientMode {
+  /// Get the keyword for this mode.
+  #[must_use]
+  #[inline]
+  pub fn as_str(self) -> &'static str {
+    match self {
+      ClientMode::Auto => "auto",
+      ClientMode::NavigateNew => "navigate-new",
+      ClientMode::NavigateExisting => "navigate-existing",
+      ClientMode::FocusExisting => "focus-existing",
+    }
+  }
+}
+
+/// Control how the application is launched, e.g. to reuse an open window
+/// when a link to it is followed.
+/// [Read more.](https://wicg.github.io/web-app-launch/)
+///
+/// `client_mode` can be a single mode or a list of modes in order of
+/// preference. It is serialized as a string if it holds one mode, and as an
+/// array otherwise.
+///
+/// ## Example Output
+/// ```json
+/// "launch_handler": {
+///   "client_mode": ["focus-existing", "auto"]
+/// }
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LaunchHandler {
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  #[serde(serialize_with = "serialize_client_mode")]
+  #[serde(default, deserialize_with = "cow::one_or_many")]
+  client_mode: Vec<ClientMode>,
+}
+
+impl LaunchHandler {
+  /// Create a new `LaunchHandler` instance without any client modes.
+  ///
+  /// ## Example
+  /// ```rust
+  /// # extern crate webmanifest;
+  /// # use webmanifest::{ClientMode, LaunchHandler};
+  /// let launch_handler = LaunchHandler::new()
+  ///   .client_mode(ClientMode::FocusExisting)
+  ///   .client_mode(ClientMode::Auto);
+  /// ```
+  #[must_use]
+  #[inline]
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Add a mode to the `client_mode` list. Modes are in order of preference.
+  #[must_use]
+  #[inline]
+  pub fn client_mode(mut self, mode: ClientMode) -> Self {
+    self.client_mode.push(mode);
+    self
+  }
+
+  /// Get the `client_mode` value.
+  #[must_use]
+  #[inline]
+  pub fn get_client_mode(&self) -> &[ClientMode] {
+    &self.client_mode
+  }
+
+  /// Compute the client mode a user agent that supports the modes in
+  /// `supported` uses: the first mode of `client_mode` it supports.
+  /// `ClientMode::Auto` is always supported, and is the result if no other
+  /// mode applies.
+  ///
+  /// ## Example
+  /// ```rust
+  /// # extern crate webmanifest;
+  /// # use webmanifest::{ClientMode, LaunchHandler};
+  /// let launch_handler = LaunchHandler::new()
+  ///   .client_mode(ClientMode::FocusExisting)
+  ///   .client_mode(ClientMode::NavigateExisting);
+  ///
+  /// let supported = [ClientMode::NavigateNew, ClientMode::NavigateExisting];
+  /// let mode = launch_handler.resolve_client_mode(&supported);
+  /// assert_eq!(mode, ClientMode::NavigateExisting);
+  /// assert_eq!(launch_handler.resolve_client_mode(&[]), ClientMode::Auto);
+  /// ```
+  #[must_use]
+  pub fn resolve_client_mode(&self, supported: &[ClientMode]) -> ClientMode {
+    self
+      .client_mode
+      .iter()
+      .find(|mode| **mode == ClientMode::Auto || supported.contains(mode))
+      .cloned()
+      .unwrap_or(ClientMode::Auto)
+  }
+
+  /// Process the `launch_handler` member, skipping unknown client modes.
+  pub(crate) fn process(
+    raw: &RawValue,
+    path: &str,
+    diagnostics: &mut Vec<Diagnostic>,
+  ) -> Option<Self> {
+    let mut processor = Processor::object(raw, path, diagnostics)?;
+    let client_mode = processor.one_or_many("client_mode", keyword);
+    processor.finish(diagnostics);
+    Some(Self { client_mode })
+  }
+}
+
+/// Serialize the `client_mode` member as a string if it holds one mode, and
+/// as an array otherwise.
+fn serialize_client_mode<S: Serializer>(
+  client_mode: &[ClientMode],
+  serializer: S,
+) -> Result<S::Ok, S::Error> {
+  match client_mode {
+    [mode] => mode.serialize(serializer),
+    modes => modes.serialize(serializer),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::super::{DiagnosticKind, Manifest};
+  use super::*;
+
+  fn client_mode(json: &str) -> Vec<ClientMode> {
+    let manifest = Manifest::parse(json).unwrap();
+    manifest
+      .get_launch_handler()
+      .unwrap()
+      .get_client_mode()
+      .to_vec()
+  }
+
+  #[test]
+  fn client_mode_is_a_string_or_an_array() {
+    let json = r#"{
+      "name": "App",
+      "launch_handler": { "client_mode": "focus-existing" }
+    }"#;
+    assert_eq!(client_mode(json), [ClientMode::FocusExisting]);
+
+    let json = r#"{
+      "name": "App",
+      "launch_handler": { "client_mode": ["navigate-existing", "auto"] }
+    }"#;
+    let modes = [ClientMode::NavigateExisting, ClientMode::Auto];
+    assert_eq!(client_mode(json), modes);
+
+    let json = r#"{ "name": "App", "launch_handler": {} }"#;
+    assert!(client_mode(json).is_empty());
+  }
+
+  #[test]
+  fn client_mode_serializes_as_a_string_if_there_is_one() {
+    let build = |launch_handler| {
+      Manifest::builder("App")
+        .launch_handler(launch_handler)
+        .build()
+        .unwrap()
+    };
+    let json = build(LaunchHandler::new().client_mode(ClientMode::NavigateNew));
+    assert!(json.contains(r#""client_mode":"navigate-new""#), "{}", json);
+    let json = build(
+      LaunchHandler::new()
+        .client_mode(ClientMode::FocusExisting)
+        .client_mode(ClientMode::Auto),
+    );
+    let expected = r#""client_mode":["focus-existing","auto"]"#;
+    assert!(json.contains(expected), "{}", json);
+    let json = build(LaunchHandler::new());
+    assert!(json.contains(r#""launch_handler":{}"#), "{}", json);
+  }
+
+  #[test]
+  fn process_skips_unknown_client_modes() {
+    let json = r#"{
+      "name": "App",
+      "launch_handler": { "client_mode": ["focus-any", "navigate-new"] }
+    }"#;
+    let (manifest, diagnostics) = Manifest::process(json).unwrap();
+    let launch_handler = manifest.get_launch_handler().unwrap();
+    assert_eq!(launch_handler.get_client_mode(), [ClientMode::NavigateNew]);
+    assert_eq!(diagnostics.len(), 1);
+    let kind = DiagnosticKind::InvalidValue {
+      value: "focus-any".to_string(),
+    };
+    assert_eq!(diagnostics[0].get_kind(), &kind);
+  }
+
+  #[test]
+  fn auto_stops_the_search() {
+    let launch_handler = LaunchHandler::new()
+      .client_mode(ClientMode::Auto)
+      .client_mode(ClientMode::FocusExisting);
+    let supported = [ClientMode::FocusExisting];
+    let mode = launch_handler.resolve_client_mode(&supported);
+    assert_eq!(mode, ClientMode::Auto);
+    let mode = LaunchHandler::new().resolve_client_mode(&supported);
+    assert_eq!(mode, ClientMode::Auto);
+  }
+}
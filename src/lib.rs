@@ -4,81 +4,267 @@
 //! ## Example
 //! ```rust
 //! extern crate webmanifest;
-//! extern crate failure;
 //!
 //! use webmanifest::{Manifest, Related};
 //!
-//! fn main() -> Result<(), failure::Error> {
+//! fn main() -> Result<(), webmanifest::Error> {
 //!   let name = "My Cool Application";
 //!   let url = "https://play.google.com/store/apps/details?id=cheeaun.hackerweb";
 //!   let manifest = Manifest::builder(name)
 //!     .short_name("my app")
 //!     .bg_color("#000")
-//!     .related(&Related::new("play", url))
+//!     .related(Related::new("play", url))
 //!     .build()?;
 //!   Ok(())
 //! }
 //! ```
 
-extern crate failure;
+#[cfg(feature = "image")]
+extern crate image;
 extern crate mime_guess;
 extern crate serde;
 extern crate serde_json;
+extern crate unicode_segmentation;
+extern crate url;
 #[macro_use]
 extern crate serde_derive;
 
-use failure::Error;
+use std::borrow::Cow;
 
+mod assets;
+mod color;
+mod contrast;
+mod cow;
+mod diagnostic;
 mod direction;
 mod display_mode;
+mod error;
+mod file_handler;
+mod form_factor;
+#[cfg(feature = "image")]
+mod generate;
 mod icon;
+mod language_tag;
+mod launch_handler;
 mod orientation;
+mod percent;
+mod process;
+mod protocol_handler;
+mod purpose;
 mod related;
+mod resolve;
+#[cfg(feature = "image")]
+mod safe_zone;
+mod screenshot;
+mod select;
+mod share_target;
+mod shortcut;
+mod sizes;
+#[cfg(all(test, feature = "image"))]
+mod test_util;
+mod validate;
 
+pub use color::Color;
+pub use diagnostic::{Diagnostic, DiagnosticKind, Severity};
 pub use direction::Direction;
 pub use display_mode::DisplayMode;
+pub use error::Error;
+pub use file_handler::{FileHandler, LaunchType};
+pub use form_factor::FormFactor;
+#[cfg(feature = "image")]
+pub use generate::IconGenerator;
 pub use icon::Icon;
+pub use language_tag::LanguageTag;
+pub use launch_handler::{ClientMode, LaunchHandler};
 pub use orientation::Orientation;
-pub use related::Related;
+pub use protocol_handler::ProtocolHandler;
+pub use purpose::{Purpose, Purposes};
+pub use related::{Fingerprint, Related};
+pub use resolve::ResolvedUrls;
+#[cfg(feature = "image")]
+pub use safe_zone::SafeZoneReport;
+pub use screenshot::Screenshot;
+pub use select::IconQuery;
+pub use share_target::{
+  ShareEnctype, ShareFile, ShareMethod, ShareParams, ShareTarget, SharedData,
+  SharedFile,
+};
+pub use shortcut::Shortcut;
+pub use sizes::{Size, Sizes};
+pub use url::Url;
+pub use validate::Validator;
 
 /// The MIME type for `.webmanifest` files.
 pub const MIME_TYPE_STR: &str = "application/manifest+json";
 
 /// Create a new manifest builder.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Manifest<'s, 'i, 'r> {
-  name: &'s str,
+pub struct Manifest<'a> {
+  #[serde(borrow)]
+  name: Cow<'a, str>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  #[serde(borrow, default, deserialize_with = "cow::option")]
+  id: Option<Cow<'a, str>>,
   #[serde(skip_serializing_if = "Option::is_none")]
-  short_name: Option<&'s str>,
+  #[serde(borrow, default, deserialize_with = "cow::option")]
+  short_name: Option<Cow<'a, str>>,
   #[serde(skip_serializing_if = "Option::is_none")]
-  start_url: Option<&'s str>,
+  #[serde(borrow, default, deserialize_with = "cow::option")]
+  start_url: Option<Cow<'a, str>>,
   #[serde(skip_serializing_if = "Option::is_none")]
   #[serde(rename = "display")]
   display_mode: Option<DisplayMode>,
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  #[serde(default)]
+  display_override: Vec<DisplayMode>,
   #[serde(skip_serializing_if = "Option::is_none")]
-  background_color: Option<&'s str>,
+  #[serde(borrow, default, deserialize_with = "cow::option")]
+  background_color: Option<Cow<'a, str>>,
   #[serde(skip_serializing_if = "Option::is_none")]
-  description: Option<&'s str>,
+  #[serde(borrow, default, deserialize_with = "cow::option")]
+  description: Option<Cow<'a, str>>,
   #[serde(skip_serializing_if = "Option::is_none")]
   #[serde(rename = "dir")]
   direction: Option<Direction>,
   #[serde(skip_serializing_if = "Option::is_none")]
   orientation: Option<Orientation>,
   #[serde(skip_serializing_if = "Option::is_none")]
-  lang: Option<&'s str>,
+  #[serde(borrow, default, deserialize_with = "cow::option")]
+  lang: Option<Cow<'a, str>>,
   #[serde(skip_serializing_if = "Option::is_none")]
-  scope: Option<&'s str>,
+  #[serde(borrow, default, deserialize_with = "cow::option")]
+  scope: Option<Cow<'a, str>>,
   #[serde(skip_serializing_if = "Option::is_none")]
-  theme_color: Option<&'s str>,
+  #[serde(borrow, default, deserialize_with = "cow::option")]
+  theme_color: Option<Cow<'a, str>>,
   #[serde(skip_serializing_if = "Option::is_none")]
   prefer_related_applications: Option<bool>,
-  #[serde(borrow)]
-  icons: Vec<Icon<'i>>,
-  #[serde(borrow)]
-  related_applications: Vec<Related<'r>>,
+  #[serde(borrow, default)]
+  icons: Vec<Icon<'a>>,
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  #[serde(borrow, default)]
+  screenshots: Vec<Screenshot<'a>>,
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  #[serde(borrow, default)]
+  shortcuts: Vec<Shortcut<'a>>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  #[serde(borrow, default)]
+  share_target: Option<ShareTarget<'a>>,
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  #[serde(borrow, default)]
+  protocol_handlers: Vec<ProtocolHandler<'a>>,
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  #[serde(borrow, default)]
+  file_handlers: Vec<FileHandler<'a>>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  launch_handler: Option<LaunchHandler>,
+  #[serde(borrow, default)]
+  related_applications: Vec<Related<'a>>,
 }
 
-impl<'s, 'i, 'r> Manifest<'s, 'i, 'r> {
+impl<'a> Manifest<'a> {
+  /// Parse a manifest from a JSON string.
+  ///
+  /// Strings in the returned manifest borrow from the input where possible.
+  /// Use `Manifest::into_owned` to detach the manifest from the input.
+  ///
+  /// ## Example
+  /// ```rust
+  /// # extern crate webmanifest;
+  /// # use webmanifest::Manifest;
+  /// # fn main() -> Result<(), webmanifest::Error> {
+  /// let json = r#"{ "name": "My Cool Application", "short_name": "Cool App" }"#;
+  /// let manifest = Manifest::parse(json)?;
+  /// assert_eq!(manifest.get_name(), "My Cool Application");
+  /// assert_eq!(manifest.get_short_name(), Some("Cool App"));
+  /// # Ok(())}
+  /// ```
+  #[inline]
+  pub fn parse(json: &'a str) -> Result<Self, Error> {
+    serde_json::from_str(json).map_err(Error::Parse)
+  }
+
+  /// Parse a manifest from a JSON byte slice.
+  ///
+  /// ## Example
+  /// ```rust
+  /// # extern crate webmanifest;
+  /// # use webmanifest::Manifest;
+  /// # fn main() -> Result<(), webmanifest::Error> {
+  /// let json = br#"{ "name": "My Cool Application", "icons": [] }"#;
+  /// let manifest = Manifest::from_slice(json)?;
+  /// assert!(manifest.get_icons().is_empty());
+  /// # Ok(())}
+  /// ```
+  #[inline]
+  pub fn from_slice(json: &'a [u8]) -> Result<Self, Error> {
+    serde_json::from_slice(json).map_err(Error::Parse)
+  }
+
+  /// Convert into a manifest that owns all of its strings.
+  ///
+  /// ## Example
+  /// ```rust
+  /// # extern crate webmanifest;
+  /// # use webmanifest::Manifest;
+  /// # fn main() -> Result<(), webmanifest::Error> {
+  /// fn load() -> Result<Manifest<'static>, webmanifest::Error> {
+  ///   let json = String::from(r#"{ "name": "My Cool Application" }"#);
+  ///   Ok(Manifest::parse(&json)?.into_owned())
+  /// }
+  /// let manifest = load()?;
+  /// # Ok(())}
+  /// ```
+  #[must_use]
+  pub fn into_owned(self) -> Manifest<'static> {
+    Manifest {
+      name: cow::owned(self.name),
+      id: cow::owned_option(self.id),
+      short_name: cow::owned_option(self.short_name),
+      start_url: cow::owned_option(self.start_url),
+      display_mode: self.display_mode,
+      display_override: self.display_override,
+      background_color: cow::owned_option(self.background_color),
+      description: cow::owned_option(self.description),
+      direction: self.direction,
+      orientation: self.orientation,
+      lang: cow::owned_option(self.lang),
+      scope: cow::owned_option(self.scope),
+      theme_color: cow::owned_option(self.theme_color),
+      prefer_related_applications: self.prefer_related_applications,
+      icons: self.icons.into_iter().map(Icon::into_owned).collect(),
+      screenshots: self
+        .screenshots
+        .into_iter()
+        .map(Screenshot::into_owned)
+        .collect(),
+      shortcuts: self
+        .shortcuts
+        .into_iter()
+        .map(Shortcut::into_owned)
+        .collect(),
+      share_target: self.share_target.map(ShareTarget::into_owned),
+      protocol_handlers: self
+        .protocol_handlers
+        .into_iter()
+        .map(ProtocolHandler::into_owned)
+        .collect(),
+      file_handlers: self
+        .file_handlers
+        .into_iter()
+        .map(FileHandler::into_owned)
+        .collect(),
+      launch_handler: self.launch_handler,
+      related_applications: self
+        .related_applications
+        .into_iter()
+        .map(Related::into_owned)
+        .collect(),
+    }
+  }
+}
+
+impl<'a> Manifest<'a> {
   /// Create a new instance.
   ///
   /// ## Example
@@ -90,13 +276,15 @@ impl<'s, 'i, 'r> Manifest<'s, 'i, 'r> {
   /// ```
   #[must_use]
   #[inline]
-  pub fn builder(name: &'s str) -> Self {
+  pub fn builder(name: impl Into<Cow<'a, str>>) -> Self {
     Self {
-      name,
+      name: name.into(),
+      id: None,
       short_name: None,
       description: None,
       start_url: None,
       display_mode: None,
+      display_override: vec![],
       orientation: None,
       direction: None,
       lang: None,
@@ -105,6 +293,12 @@ impl<'s, 'i, 'r> Manifest<'s, 'i, 'r> {
       scope: None,
       prefer_related_applications: None,
       icons: vec![],
+      screenshots: vec![],
+      shortcuts: vec![],
+      share_target: None,
+      protocol_handlers: vec![],
+      file_handlers: vec![],
+      launch_handler: None,
       related_applications: vec![],
     }
   }
@@ -114,18 +308,15 @@ impl<'s, 'i, 'r> Manifest<'s, 'i, 'r> {
   /// ## Example
   /// ```rust
   /// # extern crate webmanifest;
-  /// # extern crate failure;
   /// # use webmanifest::Manifest;
-  /// # fn main() -> Result<(), failure::Error> {
+  /// # fn main() -> Result<(), webmanifest::Error> {
   /// let name = "My Cool Application";
   /// let manifest = Manifest::builder(name).build()?;
   /// # Ok(())}
   /// ```
-  #[must_use]
   #[inline]
   pub fn build(self) -> Result<String, Error> {
-    let manifest = serde_json::to_string(&self)?;
-    Ok(manifest)
+    serde_json::to_string(&self).map_err(Error::Serialize)
   }
 
   /// Finalize the builder and create a pretty representation of the manifest.
@@ -133,31 +324,27 @@ impl<'s, 'i, 'r> Manifest<'s, 'i, 'r> {
   /// ## Example
   /// ```rust
   /// # extern crate webmanifest;
-  /// # extern crate failure;
   /// # use webmanifest::Manifest;
-  /// # fn main() -> Result<(), failure::Error> {
+  /// # fn main() -> Result<(), webmanifest::Error> {
   /// let name = "My Cool Application";
   /// let manifest = Manifest::builder(name).pretty()?;
   /// # Ok(())}
   /// ```
-  #[must_use]
   #[inline]
   pub fn pretty(self) -> Result<String, Error> {
-    let manifest = serde_json::to_string_pretty(&self)?;
-    Ok(manifest)
+    serde_json::to_string_pretty(&self).map_err(Error::Serialize)
   }
 
   /// Set the `short_name` value.
   ///
-  /// ## Panics
-  /// This will panic if the short name exceeds 12 characters.
+  /// Launchers truncate long short names; `Manifest::validate` warns when the
+  /// value exceeds 12 user-perceived characters.
   ///
   /// ## Example
   /// ```rust
   /// # extern crate webmanifest;
-  /// # extern crate failure;
   /// # use webmanifest::Manifest;
-  /// # fn main() -> Result<(), failure::Error> {
+  /// # fn main() -> Result<(), webmanifest::Error> {
   /// let name = "My Cool Application";
   /// let manifest = Manifest::builder(name)
   ///   .short_name("Cool App")
@@ -166,9 +353,33 @@ impl<'s, 'i, 'r> Manifest<'s, 'i, 'r> {
   /// ```
   #[must_use]
   #[inline]
-  pub fn short_name(mut self, name: &'s str) -> Self {
-    debug_assert!(name.len() <= 12);
-    self.short_name = Some(name);
+  pub fn short_name(mut self, name: impl Into<Cow<'a, str>>) -> Self {
+    self.short_name = Some(name.into());
+    self
+  }
+
+  /// Set the `id` value.
+  ///
+  /// Identifies the application independently of `start_url`, so the
+  /// `start_url` can change without user agents treating the manifest as a new
+  /// application. Resolved against the origin of `start_url`; see
+  /// `Manifest::identity`.
+  ///
+  /// ## Example
+  /// ```rust
+  /// # extern crate webmanifest;
+  /// # use webmanifest::Manifest;
+  /// # fn main() -> Result<(), webmanifest::Error> {
+  /// let name = "My Cool Application";
+  /// let manifest = Manifest::builder(name)
+  ///   .id("/cool-app")
+  ///   .build()?;
+  /// # Ok(())}
+  /// ```
+  #[must_use]
+  #[inline]
+  pub fn id(mut self, id: impl Into<Cow<'a, str>>) -> Self {
+    self.id = Some(id.into());
     self
   }
 
@@ -177,9 +388,8 @@ impl<'s, 'i, 'r> Manifest<'s, 'i, 'r> {
   /// ## Example
   /// ```rust
   /// # extern crate webmanifest;
-  /// # extern crate failure;
   /// # use webmanifest::Manifest;
-  /// # fn main() -> Result<(), failure::Error> {
+  /// # fn main() -> Result<(), webmanifest::Error> {
   /// let name = "My Cool Application";
   /// let manifest = Manifest::builder(name)
   ///   .start_url(".")
@@ -188,8 +398,8 @@ impl<'s, 'i, 'r> Manifest<'s, 'i, 'r> {
   /// ```
   #[must_use]
   #[inline]
-  pub fn start_url(mut self, url: &'s str) -> Self {
-    self.start_url = Some(url);
+  pub fn start_url(mut self, url: impl Into<Cow<'a, str>>) -> Self {
+    self.start_url = Some(url.into());
     self
   }
 
@@ -198,9 +408,8 @@ impl<'s, 'i, 'r> Manifest<'s, 'i, 'r> {
   /// ## Example
   /// ```rust
   /// # extern crate webmanifest;
-  /// # extern crate failure;
   /// # use webmanifest::{Manifest, DisplayMode};
-  /// # fn main() -> Result<(), failure::Error> {
+  /// # fn main() -> Result<(), webmanifest::Error> {
   /// let name = "My Cool Application";
   /// let manifest = Manifest::builder(name)
   ///   .display_mode(DisplayMode::Standalone)
@@ -214,14 +423,36 @@ impl<'s, 'i, 'r> Manifest<'s, 'i, 'r> {
     self
   }
 
-  /// Set the `background_color` value.
+  /// Add a mode to the `display_override` list. User agents use the first
+  /// mode of the list they support, before considering `display`.
+  ///
+  /// ## Example
+  /// ```rust
+  /// # extern crate webmanifest;
+  /// # use webmanifest::{Manifest, DisplayMode};
+  /// # fn main() -> Result<(), webmanifest::Error> {
+  /// let name = "My Cool Application";
+  /// let manifest = Manifest::builder(name)
+  ///   .display_mode(DisplayMode::Standalone)
+  ///   .display_override(DisplayMode::WindowControlsOverlay)
+  ///   .display_override(DisplayMode::MinimalUi)
+  ///   .build()?;
+  /// # Ok(())}
+  /// ```
+  #[must_use]
+  #[inline]
+  pub fn display_override(mut self, mode: DisplayMode) -> Self {
+    self.display_override.push(mode);
+    self
+  }
+
+  /// Set the `background_color` value. Accepts a string or a `Color`.
   ///
   /// ## Example
   /// ```rust
   /// # extern crate webmanifest;
-  /// # extern crate failure;
   /// # use webmanifest::Manifest;
-  /// # fn main() -> Result<(), failure::Error> {
+  /// # fn main() -> Result<(), webmanifest::Error> {
   /// let name = "My Cool Application";
   /// let manifest = Manifest::builder(name)
   ///   .bg_color("#000")
@@ -230,19 +461,18 @@ impl<'s, 'i, 'r> Manifest<'s, 'i, 'r> {
   /// ```
   #[must_use]
   #[inline]
-  pub fn bg_color(mut self, color: &'s str) -> Self {
-    self.background_color = Some(color);
+  pub fn bg_color(mut self, color: impl Into<Cow<'a, str>>) -> Self {
+    self.background_color = Some(color.into());
     self
   }
 
-  /// Set the `theme_color` value.
+  /// Set the `theme_color` value. Accepts a string or a `Color`.
   ///
   /// ## Example
   /// ```rust
   /// # extern crate webmanifest;
-  /// # extern crate failure;
   /// # use webmanifest::Manifest;
-  /// # fn main() -> Result<(), failure::Error> {
+  /// # fn main() -> Result<(), webmanifest::Error> {
   /// let name = "My Cool Application";
   /// let manifest = Manifest::builder(name)
   ///   .theme_color("#000")
@@ -251,8 +481,8 @@ impl<'s, 'i, 'r> Manifest<'s, 'i, 'r> {
   /// ```
   #[must_use]
   #[inline]
-  pub fn theme_color(mut self, color: &'s str) -> Self {
-    self.theme_color = Some(color);
+  pub fn theme_color(mut self, color: impl Into<Cow<'a, str>>) -> Self {
+    self.theme_color = Some(color.into());
     self
   }
 
@@ -261,9 +491,8 @@ impl<'s, 'i, 'r> Manifest<'s, 'i, 'r> {
   /// ## Example
   /// ```rust
   /// # extern crate webmanifest;
-  /// # extern crate failure;
   /// # use webmanifest::Manifest;
-  /// # fn main() -> Result<(), failure::Error> {
+  /// # fn main() -> Result<(), webmanifest::Error> {
   /// let name = "My Cool Application";
   /// let desc = "It does many things.";
   /// let manifest = Manifest::builder(name)
@@ -273,22 +502,22 @@ impl<'s, 'i, 'r> Manifest<'s, 'i, 'r> {
   /// ```
   #[must_use]
   #[inline]
-  pub fn description(mut self, desc: &'s str) -> Self {
-    self.description = Some(desc);
+  pub fn description(mut self, desc: impl Into<Cow<'a, str>>) -> Self {
+    self.description = Some(desc.into());
     self
   }
 
   /// Set the `lang` value.
   ///
   /// Specifies the primary language for the values in the name and short_name
-  /// members. This value is a string containing a single language tag.
+  /// members. This value is a string containing a single BCP 47 language tag.
+  /// Accepts a string or a `LanguageTag`.
   ///
   /// ## Example
   /// ```rust
   /// # extern crate webmanifest;
-  /// # extern crate failure;
   /// # use webmanifest::Manifest;
-  /// # fn main() -> Result<(), failure::Error> {
+  /// # fn main() -> Result<(), webmanifest::Error> {
   /// let name = "My Cool Application";
   /// let lang = "en-US";
   /// let manifest = Manifest::builder(name)
@@ -298,8 +527,8 @@ impl<'s, 'i, 'r> Manifest<'s, 'i, 'r> {
   /// ```
   #[must_use]
   #[inline]
-  pub fn lang(mut self, lang: &'s str) -> Self {
-    self.lang = Some(lang);
+  pub fn lang(mut self, lang: impl Into<Cow<'a, str>>) -> Self {
+    self.lang = Some(lang.into());
     self
   }
 
@@ -313,9 +542,8 @@ impl<'s, 'i, 'r> Manifest<'s, 'i, 'r> {
   /// ## Example
   /// ```rust
   /// # extern crate webmanifest;
-  /// # extern crate failure;
   /// # use webmanifest::{Manifest, Orientation};
-  /// # fn main() -> Result<(), failure::Error> {
+  /// # fn main() -> Result<(), webmanifest::Error> {
   /// let name = "My Cool Application";
   /// let manifest = Manifest::builder(name)
   ///   .orientation(Orientation::Portrait)
@@ -338,9 +566,8 @@ impl<'s, 'i, 'r> Manifest<'s, 'i, 'r> {
   /// ## Example
   /// ```rust
   /// # extern crate webmanifest;
-  /// # extern crate failure;
   /// # use webmanifest::{Manifest, Direction};
-  /// # fn main() -> Result<(), failure::Error> {
+  /// # fn main() -> Result<(), webmanifest::Error> {
   /// let name = "My Cool Application";
   /// let lang = "en-US";
   /// let manifest = Manifest::builder(name)
@@ -368,9 +595,8 @@ impl<'s, 'i, 'r> Manifest<'s, 'i, 'r> {
   /// ## Example
   /// ```rust
   /// # extern crate webmanifest;
-  /// # extern crate failure;
   /// # use webmanifest::{Manifest, Direction};
-  /// # fn main() -> Result<(), failure::Error> {
+  /// # fn main() -> Result<(), webmanifest::Error> {
   /// let name = "My Cool Application";
   /// let manifest = Manifest::builder(name)
   ///   .prefer_related_applications(true)
@@ -397,9 +623,8 @@ impl<'s, 'i, 'r> Manifest<'s, 'i, 'r> {
   /// ## Example
   /// ```rust
   /// # extern crate webmanifest;
-  /// # extern crate failure;
   /// # use webmanifest::{Manifest, Direction};
-  /// # fn main() -> Result<(), failure::Error> {
+  /// # fn main() -> Result<(), webmanifest::Error> {
   /// let name = "My Cool Application";
   /// let manifest = Manifest::builder(name)
   ///   .scope("/myapp/")
@@ -408,52 +633,436 @@ impl<'s, 'i, 'r> Manifest<'s, 'i, 'r> {
   /// ```
   #[must_use]
   #[inline]
-  pub fn scope(mut self, scope: &'s str) -> Self {
-    self.scope = Some(scope);
+  pub fn scope(mut self, scope: impl Into<Cow<'a, str>>) -> Self {
+    self.scope = Some(scope.into());
     self
   }
 
-  /// Add an `Icon` to the icons vector.
+  /// Add an `Icon` to the icons vector. Accepts an `Icon` or a reference to
+  /// one.
   ///
   /// ## Example
   /// ```rust
   /// # extern crate webmanifest;
-  /// # extern crate failure;
   /// # use webmanifest::{Manifest, Icon};
-  /// # fn main() -> Result<(), failure::Error> {
+  /// # fn main() -> Result<(), webmanifest::Error> {
   /// let name = "My Cool Application";
   /// let src = "images/touch/homescreen48.png";
   /// let manifest = Manifest::builder(name)
-  ///   .icon(&Icon::new(&src, "48x48"))
+  ///   .icon(Icon::new(src, (48, 48)))
+  ///   .build()?;
+  /// # Ok(())}
+  /// ```
+  #[must_use]
+  #[inline]
+  pub fn icon(mut self, icon: impl Into<Icon<'a>>) -> Self {
+    self.icons.push(icon.into());
+    self
+  }
+
+  /// Add a `Screenshot` to the screenshots vector. Accepts a `Screenshot` or
+  /// a reference to one.
+  ///
+  /// ## Example
+  /// ```rust
+  /// # extern crate webmanifest;
+  /// # use webmanifest::{FormFactor, Manifest, Screenshot};
+  /// # fn main() -> Result<(), webmanifest::Error> {
+  /// let name = "My Cool Application";
+  /// let manifest = Manifest::builder(name)
+  ///   .screenshot(
+  ///     Screenshot::new("screenshots/desktop.png", (1280, 720))
+  ///       .form_factor(FormFactor::Wide),
+  ///   )
+  ///   .screenshot(
+  ///     Screenshot::new("screenshots/mobile.png", (720, 1280))
+  ///       .form_factor(FormFactor::Narrow),
+  ///   )
+  ///   .build()?;
+  /// # Ok(())}
+  /// ```
+  #[must_use]
+  #[inline]
+  pub fn screenshot(mut self, screenshot: impl Into<Screenshot<'a>>) -> Self {
+    self.screenshots.push(screenshot.into());
+    self
+  }
+
+  /// Add a `Shortcut` to the shortcuts vector. Accepts a `Shortcut` or a
+  /// reference to one.
+  ///
+  /// ## Example
+  /// ```rust
+  /// # extern crate webmanifest;
+  /// # use webmanifest::{Icon, Manifest, Shortcut};
+  /// # fn main() -> Result<(), webmanifest::Error> {
+  /// let name = "My Cool Application";
+  /// let manifest = Manifest::builder(name)
+  ///   .shortcut(
+  ///     Shortcut::new("Open Play Later", "/play-later")
+  ///       .icon(Icon::new("/icons/play-later.png", (96, 96))),
+  ///   )
+  ///   .build()?;
+  /// # Ok(())}
+  /// ```
+  #[must_use]
+  #[inline]
+  pub fn shortcut(mut self, shortcut: impl Into<Shortcut<'a>>) -> Self {
+    self.shortcuts.push(shortcut.into());
+    self
+  }
+
+  /// Set the `share_target` value.
+  ///
+  /// ## Example
+  /// ```rust
+  /// # extern crate webmanifest;
+  /// # use webmanifest::{Manifest, ShareTarget};
+  /// # fn main() -> Result<(), webmanifest::Error> {
+  /// let name = "My Cool Application";
+  /// let manifest = Manifest::builder(name)
+  ///   .share_target(ShareTarget::new("/share").title("title").url("url"))
+  ///   .build()?;
+  /// # Ok(())}
+  /// ```
+  #[must_use]
+  #[inline]
+  pub fn share_target(mut self, share_target: ShareTarget<'a>) -> Self {
+    self.share_target = Some(share_target);
+    self
+  }
+
+  /// Add a `ProtocolHandler` to the protocol handlers vector. Accepts a
+  /// `ProtocolHandler` or a reference to one.
+  ///
+  /// ## Example
+  /// ```rust
+  /// # extern crate webmanifest;
+  /// # use webmanifest::{Manifest, ProtocolHandler};
+  /// # fn main() -> Result<(), webmanifest::Error> {
+  /// let name = "My Cool Application";
+  /// let manifest = Manifest::builder(name)
+  ///   .protocol_handler(ProtocolHandler::new("web+ourapp", "/open?target=%s"))
+  ///   .build()?;
+  /// # Ok(())}
+  /// ```
+  #[must_use]
+  #[inline]
+  pub fn protocol_handler(
+    mut self,
+    handler: impl Into<ProtocolHandler<'a>>,
+  ) -> Self {
+    self.protocol_handlers.push(handler.into());
+    self
+  }
+
+  /// Add a `FileHandler` to the file handlers vector. Accepts a
+  /// `FileHandler` or a reference to one.
+  ///
+  /// ## Example
+  /// ```rust
+  /// # extern crate webmanifest;
+  /// # use webmanifest::{FileHandler, Manifest};
+  /// # fn main() -> Result<(), webmanifest::Error> {
+  /// let name = "My Cool Application";
+  /// let manifest = Manifest::builder(name)
+  ///   .file_handler(FileHandler::new("/open-csv").accept("text/csv", [".csv"]))
   ///   .build()?;
   /// # Ok(())}
   /// ```
   #[must_use]
   #[inline]
-  pub fn icon(mut self, icon: &'i Icon) -> Self {
-    self.icons.push(icon.clone());
+  pub fn file_handler(mut self, handler: impl Into<FileHandler<'a>>) -> Self {
+    self.file_handlers.push(handler.into());
+    self
+  }
+
+  /// Set the `launch_handler` value.
+  ///
+  /// ## Example
+  /// ```rust
+  /// # extern crate webmanifest;
+  /// # use webmanifest::{ClientMode, LaunchHandler, Manifest};
+  /// # fn main() -> Result<(), webmanifest::Error> {
+  /// let name = "My Cool Application";
+  /// let manifest = Manifest::builder(name)
+  ///   .launch_handler(LaunchHandler::new().client_mode(ClientMode::FocusExisting))
+  ///   .build()?;
+  /// # Ok(())}
+  /// ```
+  #[must_use]
+  #[inline]
+  pub fn launch_handler(mut self, launch_handler: LaunchHandler) -> Self {
+    self.launch_handler = Some(launch_handler);
     self
   }
 
   /// Add an `Related` application to the `related_applications` vector.
+  /// Accepts a `Related` or a reference to one.
   ///
   /// ## Example
   /// ```rust
   /// # extern crate webmanifest;
-  /// # extern crate failure;
   /// # use webmanifest::{Manifest, Related};
-  /// # fn main() -> Result<(), failure::Error> {
+  /// # fn main() -> Result<(), webmanifest::Error> {
   /// let name = "My Cool Application";
   /// let url = "https://play.google.com/store/apps/details?id=cheeaun.hackerweb";
   /// let manifest = Manifest::builder(name)
-  ///   .related(&Related::new("play", url))
+  ///   .related(Related::new("play", url))
   ///   .build()?;
   /// # Ok(())}
   /// ```
   #[must_use]
   #[inline]
-  pub fn related(mut self, related: &'r Related) -> Self {
-    self.related_applications.push(related.clone());
+  pub fn related(mut self, related: impl Into<Related<'a>>) -> Self {
+    self.related_applications.push(related.into());
     self
   }
+
+  /// Get the `name` value.
+  #[must_use]
+  #[inline]
+  pub fn get_name(&self) -> &str {
+    &self.name
+  }
+
+  /// Get the `short_name` value.
+  #[must_use]
+  #[inline]
+  pub fn get_short_name(&self) -> Option<&str> {
+    self.short_name.as_deref()
+  }
+
+  /// Get the `id` value.
+  #[must_use]
+  #[inline]
+  pub fn get_id(&self) -> Option<&str> {
+    self.id.as_deref()
+  }
+
+  /// Get the `start_url` value.
+  #[must_use]
+  #[inline]
+  pub fn get_start_url(&self) -> Option<&str> {
+    self.start_url.as_deref()
+  }
+
+  /// Get the `display` value. Defaults to `DisplayMode::Browser` if not set.
+  #[must_use]
+  #[inline]
+  pub fn get_display_mode(&self) -> DisplayMode {
+    self.display_mode.unwrap_or(DisplayMode::Browser)
+  }
+
+  /// Get the `display_override` value.
+  #[must_use]
+  #[inline]
+  pub fn get_display_override(&self) -> &[DisplayMode] {
+    &self.display_override
+  }
+
+  /// Get the `background_color` value.
+  #[must_use]
+  #[inline]
+  pub fn get_bg_color(&self) -> Option<&str> {
+    self.background_color.as_deref()
+  }
+
+  /// Get the `background_color` value as a `Color`. Returns `None` if the
+  /// value is not set or is not a valid color.
+  #[must_use]
+  pub fn get_bg_color_value(&self) -> Option<Color> {
+    Color::parse(self.get_bg_color()?).ok()
+  }
+
+  /// Get the `theme_color` value.
+  #[must_use]
+  #[inline]
+  pub fn get_theme_color(&self) -> Option<&str> {
+    self.theme_color.as_deref()
+  }
+
+  /// Get the `theme_color` value as a `Color`. Returns `None` if the value is
+  /// not set or is not a valid color.
+  ///
+  /// ## Example
+  /// ```rust
+  /// # extern crate webmanifest;
+  /// # use webmanifest::{Color, Manifest};
+  /// let manifest = Manifest::builder("My Cool Application")
+  ///   .theme_color("hsl(120deg 100% 25%)")
+  ///   .bg_color(Color::rgb(255, 255, 255));
+  /// let theme_color = manifest.get_theme_color_value().map(Color::to_hex);
+  /// assert_eq!(theme_color.as_deref(), Some("#008000"));
+  /// assert_eq!(manifest.get_bg_color(), Some("#ffffff"));
+  /// ```
+  #[must_use]
+  pub fn get_theme_color_value(&self) -> Option<Color> {
+    Color::parse(self.get_theme_color()?).ok()
+  }
+
+  /// Get the `description` value.
+  #[must_use]
+  #[inline]
+  pub fn get_description(&self) -> Option<&str> {
+    self.description.as_deref()
+  }
+
+  /// Get the `lang` value.
+  #[must_use]
+  #[inline]
+  pub fn get_lang(&self) -> Option<&str> {
+    self.lang.as_deref()
+  }
+
+  /// Get the `lang` value as a `LanguageTag`. Returns `None` if the value is
+  /// not set or is not a well-formed language tag.
+  ///
+  /// ## Example
+  /// ```rust
+  /// # extern crate webmanifest;
+  /// # use webmanifest::{Direction, Manifest};
+  /// let manifest = Manifest::builder("My Cool Application").lang("fa-ir");
+  /// let tag = manifest.get_language_tag().unwrap();
+  /// assert_eq!(tag.to_string(), "fa-IR");
+  /// assert_eq!(tag.direction(), Some(Direction::Rtl));
+  /// ```
+  #[must_use]
+  pub fn get_language_tag(&self) -> Option<LanguageTag> {
+    LanguageTag::parse(self.get_lang()?).ok()
+  }
+
+  /// Get the `orientation` value.
+  #[must_use]
+  #[inline]
+  pub fn get_orientation(&self) -> Option<&Orientation> {
+    self.orientation.as_ref()
+  }
+
+  /// Get the `dir` value. Defaults to `Direction::Auto` if not set.
+  #[must_use]
+  #[inline]
+  pub fn get_direction(&self) -> Direction {
+    self.direction.unwrap_or(Direction::Auto)
+  }
+
+  /// Get the `prefer_related_applications` value.
+  #[must_use]
+  #[inline]
+  pub fn get_prefer_related_applications(&self) -> Option<bool> {
+    self.prefer_related_applications
+  }
+
+  /// Get the `scope` value.
+  #[must_use]
+  #[inline]
+  pub fn get_scope(&self) -> Option<&str> {
+    self.scope.as_deref()
+  }
+
+  /// Get the `icons` value.
+  #[must_use]
+  #[inline]
+  pub fn get_icons(&self) -> &[Icon<'a>] {
+    &self.icons
+  }
+
+  /// Get the `screenshots` value.
+  #[must_use]
+  #[inline]
+  pub fn get_screenshots(&self) -> &[Screenshot<'a>] {
+    &self.screenshots
+  }
+
+  /// Get the `shortcuts` value.
+  #[must_use]
+  #[inline]
+  pub fn get_shortcuts(&self) -> &[Shortcut<'a>] {
+    &self.shortcuts
+  }
+
+  /// Get the `share_target` value.
+  #[must_use]
+  #[inline]
+  pub fn get_share_target(&self) -> Option<&ShareTarget<'a>> {
+    self.share_target.as_ref()
+  }
+
+  /// Get the `protocol_handlers` value.
+  #[must_use]
+  #[inline]
+  pub fn get_protocol_handlers(&self) -> &[ProtocolHandler<'a>] {
+    &self.protocol_handlers
+  }
+
+  /// Get the `file_handlers` value.
+  #[must_use]
+  #[inline]
+  pub fn get_file_handlers(&self) -> &[FileHandler<'a>] {
+    &self.file_handlers
+  }
+
+  /// Get the `launch_handler` value.
+  #[must_use]
+  #[inline]
+  pub fn get_launch_handler(&self) -> Option<&LaunchHandler> {
+    self.launch_handler.as_ref()
+  }
+
+  /// Get the `related_applications` value.
+  #[must_use]
+  #[inline]
+  pub fn get_related(&self) -> &[Related<'a>] {
+    &self.related_applications
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const ESCAPED: &str = r#"{
+    "name": "Café \"Express\"",
+    "short_name": "Café",
+    "description": "Plain text",
+    "start_url": "\/menu\/?q=a\\b",
+    "icons": [{ "src": "\/icons\/café.png", "purpose": "any\tmaskable" }],
+    "related_applications": [{ "platform": "play", "id": "com.café" }]
+  }"#;
+
+  fn assert_unescaped(manifest: &Manifest) {
+    assert_eq!(manifest.get_name(), "Café \"Express\"");
+    assert_eq!(manifest.get_short_name(), Some("Café"));
+    assert_eq!(manifest.get_description(), Some("Plain text"));
+    assert_eq!(manifest.get_start_url(), Some("/menu/?q=a\\b"));
+    let icon = &manifest.get_icons()[0];
+    assert_eq!(icon.get_src(), "/icons/café.png");
+    assert_eq!(icon.get_purposes().to_string(), "any maskable");
+    assert_eq!(manifest.get_related()[0].get_id(), Some("com.café"));
+  }
+
+  #[test]
+  fn parses_escaped_strings() {
+    let manifest = Manifest::parse(ESCAPED).unwrap();
+    assert_unescaped(&manifest);
+    assert!(matches!(manifest.name, Cow::Owned(_)));
+    assert!(matches!(manifest.description, Some(Cow::Borrowed(_))));
+
+    let manifest = Manifest::from_slice(ESCAPED.as_bytes()).unwrap();
+    assert_unescaped(&manifest);
+    assert_unescaped(&manifest.into_owned());
+  }
+
+  #[test]
+  fn processes_escaped_strings() {
+    let (manifest, diagnostics) = Manifest::process(ESCAPED).unwrap();
+    assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+    assert_unescaped(&manifest);
+  }
+
+  #[test]
+  fn escaped_strings_round_trip() {
+    let json = Manifest::parse(ESCAPED).unwrap().build().unwrap();
+    assert_unescaped(&Manifest::parse(&json).unwrap());
+  }
 }
@@ -4,16 +4,15 @@
 /// ## Example
 /// ```rust
 /// # extern crate webmanifest;
-/// # extern crate failure;
 /// # use webmanifest::{Manifest, Orientation};
-/// # fn main() -> Result<(), failure::Error> {
+/// # fn main() -> Result<(), webmanifest::Error> {
 /// let name = "My Cool Application";
 /// let manifest = Manifest::builder(name)
 ///   .orientation(Orientation::Portrait)
 ///   .build()?;
 /// # Ok(())}
 /// ```
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Orientation {
   /// Enable `portrait-primary`, `portrait-secondary`, `landscape-primary` and
   /// `landscape-secondary` orientations.
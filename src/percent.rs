@@ -0,0 +1,53 @@
+//! Percent-decoding, shared by URL paths and form bodies.
+
+use std::str;
+
+/// Decode the `%XX` escapes in a URL path segment or parameter value.
+pub(crate) fn decode(value: &str) -> Vec<u8> {
+  let bytes = value.as_bytes();
+  let mut decoded = Vec::with_capacity(bytes.len());
+  let mut index = 0;
+  while index < bytes.len() {
+    let escape = bytes
+      .get(index + 1..index + 3)
+      .filter(|hex| hex.iter().all(u8::is_ascii_hexdigit))
+      .and_then(|hex| {
+        let hex = str::from_utf8(hex).ok()?;
+        u8::from_str_radix(hex, 16).ok()
+      });
+    match (bytes[index], escape) {
+      (b'%', Some(byte)) => {
+        decoded.push(byte);
+        index += 3;
+      }
+      (byte, _) => {
+        decoded.push(byte);
+        index += 1;
+      }
+    }
+  }
+  decoded
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn decodes_escapes() {
+    assert_eq!(decode("a%20b"), b"a b");
+    assert_eq!(decode("%2e%2E%2F"), b"../");
+    assert_eq!(decode("%C3%A9"), "é".as_bytes());
+    assert_eq!(decode("%FF"), [0xff]);
+    assert_eq!(decode("+"), b"+");
+  }
+
+  #[test]
+  fn keeps_malformed_escapes() {
+    assert_eq!(decode("%"), b"%");
+    assert_eq!(decode("%2"), b"%2");
+    assert_eq!(decode("%zz"), b"%zz");
+    assert_eq!(decode("%%41"), b"%A");
+    assert_eq!(decode("%+1"), b"%+1");
+  }
+}
@@ -0,0 +1,423 @@
+use serde::de::{DeserializeOwned, MapAccess, Visitor};
+use serde::{Deserialize, Deserializer};
+use serde_json::value::RawValue;
+use serde_json::{self, Value};
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt;
+
+use super::cow::CowStr;
+use super::{
+  Color, Diagnostic, DiagnosticKind, DisplayMode, Error, FileHandler, Icon,
+  LanguageTag, LaunchHandler, Manifest, ProtocolHandler, Related, Screenshot,
+  ShareTarget, Shortcut, Sizes,
+};
+
+pub(crate) type Members<'a> = HashMap<String, &'a RawValue>;
+
+impl<'a> Manifest<'a> {
+  /// Parse a manifest the way a user agent does.
+  ///
+  /// Unlike `Manifest::parse`, this follows the steps for [processing a
+  /// manifest](https://www.w3.org/TR/appmanifest/#processing): members with
+  /// the wrong type or an unsupported value are dropped, invalid entries of
+  /// list members such as `icons` are skipped, and every dropped value is
+  /// reported as a `Diagnostic`. A `display` mode that is only valid in
+  /// `display_override` is dropped too. Dropped members are left unset rather
+  /// than replaced by their defaults, so that serializing the manifest again
+  /// doesn't add members; getters such as `Manifest::get_display_mode` apply
+  /// the defaults instead.
+  ///
+  /// A missing `name` is reported and replaced by an empty string. Only input
+  /// that is not a JSON object is an error.
+  ///
+  /// ## Example
+  /// ```rust
+  /// # extern crate webmanifest;
+  /// # use webmanifest::{Manifest, DisplayMode};
+  /// # fn main() -> Result<(), webmanifest::Error> {
+  /// let json = r#"{
+  ///   "name": "My Cool Application",
+  ///   "display": "fullest",
+  ///   "icons": [{ "src": "icon.png" }, { "sizes": "48x48" }]
+  /// }"#;
+  /// let (manifest, diagnostics) = Manifest::process(json)?;
+  /// assert_eq!(manifest.get_display_mode(), DisplayMode::Browser);
+  /// assert_eq!(manifest.get_icons().len(), 1);
+  /// assert_eq!(diagnostics.len(), 2);
+  /// # Ok(())}
+  /// ```
+  pub fn process(json: &'a str) -> Result<(Self, Vec<Diagnostic>), Error> {
+    let members: Members<'a> =
+      serde_json::from_str(json).map_err(Error::Parse)?;
+    let mut processor = Processor {
+      members,
+      prefix: String::new(),
+      diagnostics: vec![],
+    };
+
+    let name = processor.required_text("name").unwrap_or_default();
+
+    let mut manifest = Manifest::builder(name);
+    manifest.short_name = processor.text("short_name");
+    manifest.description = processor.text("description");
+    manifest.id = processor.text("id");
+    manifest.start_url = processor.text("start_url");
+    manifest.scope = processor.text("scope");
+    manifest.lang = processor.language_tag("lang");
+    manifest.background_color = processor.color("background_color");
+    manifest.theme_color = processor.color("theme_color");
+    manifest.prefer_related_applications =
+      processor.member("prefer_related_applications", "a boolean");
+    manifest.display_mode = match processor.keyword("display") {
+      Some(mode) if DisplayMode::is_override_only(mode) => {
+        let kind = DiagnosticKind::InvalidValue {
+          value: mode.as_str().to_string(),
+        };
+        processor.warn("display", kind);
+        None
+      }
+      mode => mode,
+    };
+    manifest.display_override = processor.list("display_override", keyword);
+    manifest.direction = processor.keyword("dir");
+    manifest.orientation = processor.keyword("orientation");
+    manifest.icons = processor.list("icons", Icon::process);
+    manifest.screenshots = processor.list("screenshots", Screenshot::process);
+    manifest.shortcuts = processor.list("shortcuts", Shortcut::process);
+    manifest.share_target =
+      processor.nested("share_target", ShareTarget::process);
+    manifest.protocol_handlers =
+      processor.list("protocol_handlers", ProtocolHandler::process);
+    manifest.file_handlers =
+      processor.list("file_handlers", FileHandler::process);
+    manifest.launch_handler =
+      processor.nested("launch_handler", LaunchHandler::process);
+    manifest.related_applications =
+      processor.list("related_applications", Related::process);
+
+    Ok((manifest, processor.diagnostics))
+  }
+}
+
+/// Lenient member extraction that records a `Diagnostic` for every value it
+/// drops.
+pub(crate) struct Processor<'a> {
+  members: Members<'a>,
+  prefix: String,
+  diagnostics: Vec<Diagnostic>,
+}
+
+impl<'a> Processor<'a> {
+  /// Move the recorded diagnostics into `diagnostics`.
+  pub(crate) fn finish(mut self, diagnostics: &mut Vec<Diagnostic>) {
+    diagnostics.append(&mut self.diagnostics);
+  }
+
+  /// Read a nested object, reporting `path` if it is not one.
+  pub(crate) fn object(
+    raw: &'a RawValue,
+    path: &str,
+    diagnostics: &mut Vec<Diagnostic>,
+  ) -> Option<Self> {
+    match serde_json::from_str::<Members<'a>>(raw.get()) {
+      Ok(members) => Some(Self {
+        members,
+        prefix: format!("{}.", path),
+        diagnostics: vec![],
+      }),
+      Err(_) => {
+        let kind = DiagnosticKind::InvalidType {
+          expected: "an object",
+        };
+        diagnostics.push(Diagnostic::warning(path, kind));
+        None
+      }
+    }
+  }
+
+  /// Record a warning for the member `name`.
+  pub(crate) fn warn(&mut self, name: &str, kind: DiagnosticKind) {
+    let member = format!("{}{}", self.prefix, name);
+    self.diagnostics.push(Diagnostic::warning(member, kind));
+  }
+
+  /// Read a member of type `T`, dropping it if it has the wrong type.
+  pub(crate) fn member<T>(
+    &mut self,
+    name: &str,
+    expected: &'static str,
+  ) -> Option<T>
+  where
+    T: Deserialize<'a>,
+  {
+    let raw = *self.members.get(name)?;
+    match serde_json::from_str(raw.get()) {
+      Ok(value) => Some(value),
+      Err(_) => {
+        self.warn(name, DiagnosticKind::InvalidType { expected });
+        None
+      }
+    }
+  }
+
+  /// Read a string member.
+  pub(crate) fn text(&mut self, name: &str) -> Option<Cow<'a, str>> {
+    let value: CowStr<'a> = self.member(name, "a string")?;
+    Some(value.0)
+  }
+
+  /// Read a string member that must be present.
+  pub(crate) fn required_text(&mut self, name: &str) -> Option<Cow<'a, str>> {
+    if !self.members.contains_key(name) {
+      self.warn(name, DiagnosticKind::MissingMember);
+    }
+    self.text(name)
+  }
+
+  /// Read a color member, dropping it if it is not a valid CSS color.
+  pub(crate) fn color(&mut self, name: &str) -> Option<Cow<'a, str>> {
+    let value = self.text(name)?;
+    if let Err(kind) = Color::parse_kind(&value) {
+      self.warn(name, kind);
+      return None;
+    }
+    Some(value)
+  }
+
+  /// Read a language tag member, dropping it if it is not well-formed.
+  pub(crate) fn language_tag(&mut self, name: &str) -> Option<Cow<'a, str>> {
+    let value = self.text(name)?;
+    if let Err(kind) = LanguageTag::parse_kind(&value) {
+      self.warn(name, kind);
+      return None;
+    }
+    Some(value)
+  }
+
+  /// Read a `sizes` member, dropping the tokens that are not valid sizes,
+  /// and the whole member if no valid size is left.
+  pub(crate) fn sizes(&mut self, name: &str) -> Option<Sizes> {
+    let value = self.text(name)?;
+    let (sizes, invalid) = Sizes::parse_lenient(&value);
+    if sizes.is_empty() && invalid.is_empty() {
+      let kind = DiagnosticKind::InvalidValue {
+        value: value.to_string(),
+      };
+      self.warn(name, kind);
+    }
+    for token in invalid {
+      let kind = DiagnosticKind::InvalidValue {
+        value: token.to_string(),
+      };
+      self.warn(name, kind);
+    }
+    Some(sizes).filter(|sizes| !sizes.is_empty())
+  }
+
+  /// Read a keyword member such as `display`. Keywords are matched
+  /// case-insensitively and with surrounding whitespace removed.
+  pub(crate) fn keyword<T>(&mut self, name: &str) -> Option<T>
+  where
+    T: DeserializeOwned,
+  {
+    self.nested(name, keyword)
+  }
+
+  /// Read a nested object member, processing it with `process`.
+  pub(crate) fn nested<T, F>(&mut self, name: &str, process: F) -> Option<T>
+  where
+    F: FnOnce(&'a RawValue, &str, &mut Vec<Diagnostic>) -> Option<T>,
+  {
+    let raw = *self.members.get(name)?;
+    let path = format!("{}{}", self.prefix, name);
+    process(raw, &path, &mut self.diagnostics)
+  }
+
+  /// Read a member that is either a single entry or an array of entries,
+  /// processing each entry with `process`.
+  pub(crate) fn one_or_many<T, F>(&mut self, name: &str, process: F) -> Vec<T>
+  where
+    F: Fn(&'a RawValue, &str, &mut Vec<Diagnostic>) -> Option<T>,
+  {
+    let raw = match self.members.get(name) {
+      Some(raw) => *raw,
+      None => return vec![],
+    };
+    if raw.get().starts_with('[') {
+      return self.list(name, process);
+    }
+    let path = format!("{}{}", self.prefix, name);
+    process(raw, &path, &mut self.diagnostics)
+      .into_iter()
+      .collect()
+  }
+
+  /// Read an array member, processing each entry with `process` and skipping
+  /// the entries it rejects.
+  pub(crate) fn list<T, F>(&mut self, name: &str, process: F) -> Vec<T>
+  where
+    F: Fn(&'a RawValue, &str, &mut Vec<Diagnostic>) -> Option<T>,
+  {
+    let entries: Vec<&'a RawValue> = match self.member(name, "an array") {
+      Some(entries) => entries,
+      None => return vec![],
+    };
+    let mut list = vec![];
+    for (index, raw) in entries.into_iter().enumerate() {
+      let path = format!("{}{}[{}]", self.prefix, name, index);
+      if let Some(entry) = process(raw, &path, &mut self.diagnostics) {
+        list.push(entry);
+      }
+    }
+    list
+  }
+}
+
+/// Process a string entry of a list, reporting `path` if it is not one.
+pub(crate) fn string<'a>(
+  raw: &'a RawValue,
+  path: &str,
+  diagnostics: &mut Vec<Diagnostic>,
+) -> Option<Cow<'a, str>> {
+  match serde_json::from_str::<CowStr<'a>>(raw.get()) {
+    Ok(value) => Some(value.0),
+    Err(_) => {
+      let kind = DiagnosticKind::InvalidType {
+        expected: "a string",
+      };
+      diagnostics.push(Diagnostic::warning(path, kind));
+      None
+    }
+  }
+}
+
+/// Process a keyword such as a `display` value, reporting `path` if it is
+/// not a string or not a known keyword.
+pub(crate) fn keyword<T>(
+  raw: &RawValue,
+  path: &str,
+  diagnostics: &mut Vec<Diagnostic>,
+) -> Option<T>
+where
+  T: DeserializeOwned,
+{
+  let value = string(raw, path, diagnostics)?;
+  let keyword = Value::String(value.trim().to_ascii_lowercase());
+  match serde_json::from_value(keyword) {
+    Ok(keyword) => Some(keyword),
+    Err(_) => {
+      let kind = DiagnosticKind::InvalidValue {
+        value: value.to_string(),
+      };
+      diagnostics.push(Diagnostic::warning(path, kind));
+      None
+    }
+  }
+}
+
+/// Process an object whose keys are not known in advance, keeping the order
+/// of its entries. Reports `path` if it is not an object.
+pub(crate) fn entries<'a>(
+  raw: &'a RawValue,
+  path: &str,
+  diagnostics: &mut Vec<Diagnostic>,
+) -> Option<Vec<(Cow<'a, str>, &'a RawValue)>> {
+  struct EntriesVisitor;
+
+  impl<'de> Visitor<'de> for EntriesVisitor {
+    type Value = Vec<(Cow<'de, str>, &'de RawValue)>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+      f.write_str("an object")
+    }
+
+    fn visit_map<M: MapAccess<'de>>(
+      self,
+      mut map: M,
+    ) -> Result<Self::Value, M::Error> {
+      let mut entries = vec![];
+      while let Some((key, value)) =
+        map.next_entry::<CowStr<'de>, &'de RawValue>()?
+      {
+        entries.push((key.0, value));
+      }
+      Ok(entries)
+    }
+  }
+
+  let mut deserializer = serde_json::Deserializer::from_str(raw.get());
+  match deserializer.deserialize_map(EntriesVisitor) {
+    Ok(entries) => Some(entries),
+    Err(_) => {
+      let kind = DiagnosticKind::InvalidType {
+        expected: "an object",
+      };
+      diagnostics.push(Diagnostic::warning(path, kind));
+      None
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::super::Direction;
+  use super::*;
+
+  #[test]
+  fn processes_every_display_value() {
+    let modes = [
+      ("fullscreen", DisplayMode::FullScreen),
+      ("standalone", DisplayMode::Standalone),
+      ("minimal-ui", DisplayMode::MinimalUi),
+      ("browser", DisplayMode::Browser),
+    ];
+    for &(value, mode) in &modes {
+      let json = format!(r#"{{ "name": "App", "display": "{}" }}"#, value);
+      let (manifest, diagnostics) = Manifest::process(&json).unwrap();
+      assert_eq!(manifest.get_display_mode(), mode, "{}", value);
+      assert!(diagnostics.is_empty(), "{}: {:?}", value, diagnostics);
+
+      let manifest = Manifest::parse(&json).unwrap();
+      assert_eq!(manifest.get_display_mode(), mode, "{}", value);
+      let built = manifest.build().unwrap();
+      assert!(built.contains(&format!(r#""display":"{}""#, value)));
+    }
+  }
+
+  #[test]
+  fn processes_full_screen_alias() {
+    let json = r#"{ "name": "App", "display": "full-screen" }"#;
+    let (manifest, diagnostics) = Manifest::process(json).unwrap();
+    assert_eq!(manifest.get_display_mode(), DisplayMode::FullScreen);
+    assert!(diagnostics.is_empty());
+  }
+
+  #[test]
+  fn does_not_add_default_members() {
+    let json = r#"{ "name": "App" }"#;
+    let (manifest, diagnostics) = Manifest::process(json).unwrap();
+    assert!(diagnostics.is_empty());
+    assert_eq!(manifest.get_display_mode(), DisplayMode::Browser);
+    assert_eq!(manifest.get_direction(), Direction::Auto);
+    let built = manifest.build().unwrap();
+    assert!(
+      !built.contains("display") && !built.contains("dir"),
+      "{}",
+      built
+    );
+
+    let json = r#"{ "name": "App", "display": "tabbed", "dir": "up" }"#;
+    let (manifest, diagnostics) = Manifest::process(json).unwrap();
+    assert_eq!(diagnostics.len(), 2);
+    assert_eq!(manifest.get_display_mode(), DisplayMode::Browser);
+    assert_eq!(manifest.get_direction(), Direction::Auto);
+    let built = manifest.build().unwrap();
+    assert!(
+      !built.contains("display") && !built.contains("dir"),
+      "{}",
+      built
+    );
+  }
+}
@@ -0,0 +1,213 @@
+use serde_json::value::RawValue;
+
+use super::cow;
+use super::process::Processor;
+use super::Diagnostic;
+
+use std::borrow::Cow;
+
+/// The schemes that can be handled without the `web+` prefix.
+const SAFELIST: [&str; 33] = [
+  "bitcoin",
+  "cabal",
+  "dat",
+  "did",
+  "doi",
+  "dweb",
+  "ethereum",
+  "ftp",
+  "ftps",
+  "geo",
+  "im",
+  "ipfs",
+  "ipns",
+  "irc",
+  "ircs",
+  "magnet",
+  "mailto",
+  "matrix",
+  "mms",
+  "news",
+  "nntp",
+  "openpgp4fpr",
+  "sftp",
+  "sip",
+  "sms",
+  "smsto",
+  "ssb",
+  "ssh",
+  "tel",
+  "urn",
+  "webcal",
+  "wtai",
+  "xmpp",
+];
+
+/// Register the application as a handler for a URL scheme, like
+/// `registerProtocolHandler` does.
+///
+/// ## Example Output
+/// ```json
+/// "protocol_handlers": [{
+///   "protocol": "web+ourapp",
+///   "url": "/open?target=%s"
+/// }]
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProtocolHandler<'a> {
+  #[serde(borrow)]
+  protocol: Cow<'a, str>,
+  #[serde(borrow)]
+  url: Cow<'a, str>,
+}
+
+impl<'a> ProtocolHandler<'a> {
+  /// Create a new `ProtocolHandler` instance. `url` is a template in which
+  /// `%s` is replaced by the URL being handled.
+  ///
+  /// ## Example
+  /// ```rust
+  /// # extern crate webmanifest;
+  /// # use webmanifest::ProtocolHandler;
+  /// let handler = ProtocolHandler::new("web+ourapp", "/open?target=%s");
+  /// assert!(handler.has_valid_protocol());
+  /// assert!(!ProtocolHandler::new("ourapp", "/open?target=%s").has_valid_protocol());
+  /// ```
+  #[must_use]
+  #[inline]
+  pub fn new(
+    protocol: impl Into<Cow<'a, str>>,
+    url: impl Into<Cow<'a, str>>,
+  ) -> Self {
+    Self {
+      protocol: protocol.into(),
+      url: url.into(),
+    }
+  }
+
+  /// Convert into a protocol handler that owns all of its strings.
+  #[must_use]
+  pub fn into_owned(self) -> ProtocolHandler<'static> {
+    ProtocolHandler {
+      protocol: cow::owned(self.protocol),
+      url: cow::owned(self.url),
+    }
+  }
+
+  /// Get the `protocol` value.
+  #[must_use]
+  #[inline]
+  pub fn get_protocol(&self) -> &str {
+    &self.protocol
+  }
+
+  /// Get the `url` value.
+  #[must_use]
+  #[inline]
+  pub fn get_url(&self) -> &str {
+    &self.url
+  }
+
+  /// Check if `protocol` can be handled: a scheme on the safelist of
+  /// `registerProtocolHandler`, or `web+` followed by one or more ASCII
+  /// letters. Matched case-insensitively.
+  #[must_use]
+  pub fn has_valid_protocol(&self) -> bool {
+    let protocol = self.protocol.to_ascii_lowercase();
+    match protocol.strip_prefix("web+") {
+      Some(name) => {
+        !name.is_empty() && name.bytes().all(|b| b.is_ascii_lowercase())
+      }
+      None => SAFELIST.contains(&protocol.as_str()),
+    }
+  }
+
+  /// Process an entry of the `protocol_handlers` member.
+  pub(crate) fn process(
+    raw: &'a RawValue,
+    path: &str,
+    diagnostics: &mut Vec<Diagnostic>,
+  ) -> Option<Self> {
+    let mut processor = Processor::object(raw, path, diagnostics)?;
+    let protocol = processor.required_text("protocol");
+    let url = processor.required_text("url");
+    processor.finish(diagnostics);
+    Some(Self::new(protocol?, url?))
+  }
+}
+
+impl<'a, 'b> From<&'b ProtocolHandler<'a>> for ProtocolHandler<'a> {
+  #[inline]
+  fn from(handler: &'b ProtocolHandler<'a>) -> Self {
+    handler.clone()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::super::{DiagnosticKind, Manifest, Url};
+  use super::*;
+
+  #[test]
+  fn protocol_needs_web_prefix_or_safelist() {
+    let valid = |protocol| {
+      ProtocolHandler::new(protocol, "/open?url=%s").has_valid_protocol()
+    };
+    assert!(valid("web+ourapp"));
+    assert!(valid("WEB+OurApp"));
+    assert!(valid("mailto"));
+    assert!(valid("MAILTO"));
+    assert!(valid("openpgp4fpr"));
+    assert!(!valid("web+"));
+    assert!(!valid("web+our-app"));
+    assert!(!valid("web+app2"));
+    assert!(!valid("web+é"));
+    assert!(!valid("ourapp"));
+    assert!(!valid("https"));
+    assert!(!valid("javascript"));
+    assert!(!valid(""));
+  }
+
+  #[test]
+  fn safelist_is_sorted() {
+    for pair in SAFELIST.windows(2) {
+      assert!(pair[0] < pair[1], "{} >= {}", pair[0], pair[1]);
+    }
+  }
+
+  #[test]
+  fn url_needs_one_placeholder() {
+    let base_url = Url::parse("https://e.com/manifest.json").unwrap();
+    let placeholders = |url| {
+      let manifest = Manifest::builder("App")
+        .protocol_handler(ProtocolHandler::new("web+ourapp", url));
+      manifest
+        .validate(&base_url)
+        .into_iter()
+        .filter_map(|d| match d.get_kind() {
+          DiagnosticKind::Placeholder { count } => Some(*count),
+          _ => None,
+        })
+        .collect::<Vec<_>>()
+    };
+    assert!(placeholders("/open?url=%s").is_empty());
+    assert_eq!(placeholders("/open"), [0]);
+    assert_eq!(placeholders("/open?from=%s&to=%s"), [2]);
+    assert_eq!(placeholders("/open?url=%25s"), [0]);
+  }
+
+  #[test]
+  fn validate_reports_unsupported_protocol() {
+    let base_url = Url::parse("https://e.com/manifest.json").unwrap();
+    let manifest = Manifest::builder("App")
+      .protocol_handler(ProtocolHandler::new("web+ourapp", "/open?url=%s"))
+      .protocol_handler(ProtocolHandler::new("ourapp", "/open?url=%s"));
+    let diagnostics = manifest.validate(&base_url);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].get_member(), "protocol_handlers[1].protocol");
+    let kind = DiagnosticKind::UnsupportedProtocol {
+      protocol: "ourapp".to_string(),
+    };
+    assert_eq!(diagnostics[0].get_kind(), &kind);
+  }
+}
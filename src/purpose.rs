@@ -0,0 +1,187 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use std::fmt;
+use std::iter::FromIterator;
+
+use super::cow::CowStr;
+
+/// The purpose of an icon.
+///
+/// ## Example
+/// ```rust
+/// # extern crate webmanifest;
+/// # use webmanifest::{Icon, Manifest, Purpose};
+/// # fn main() -> Result<(), webmanifest::Error> {
+/// let name = "My Cool Application";
+/// let src = "images/touch/homescreen192.png";
+/// let manifest = Manifest::builder(name)
+///   .icon(Icon::new(src, (192, 192)).purpose(Purpose::Maskable))
+///   .build()?;
+/// # Ok(())}
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Purpose {
+  /// The user agent is free to display the icon in any context. This is the
+  /// default.
+  Any,
+  /// The icon is designed with icon masks and safe zone in mind, such that any
+  /// part of the image outside the safe zone can be ignored and masked away.
+  Maskable,
+  /// The icon is a solid fill that a user agent can recolor, e.g. for
+  /// notification badges.
+  Monochrome,
+}
+
+impl Purpose {
+  const ALL: [Purpose; 3] =
+    [Purpose::Any, Purpose::Maskable, Purpose::Monochrome];
+
+  /// Parse a single purpose keyword. Keywords are matched case-insensitively.
+  ///
+  /// ## Example
+  /// ```rust
+  /// # extern crate webmanifest;
+  /// # use webmanifest::Purpose;
+  /// assert_eq!(Purpose::parse("Maskable"), Some(Purpose::Maskable));
+  /// assert_eq!(Purpose::parse("badge"), None);
+  /// ```
+  #[must_use]
+  pub fn parse(keyword: &str) -> Option<Self> {
+    Self::ALL
+      .iter()
+      .find(|purpose| purpose.as_str().eq_ignore_ascii_case(keyword))
+      .cloned()
+  }
+
+  /// Get the keyword for this purpose.
+  #[must_use]
+  #[inline]
+  pub fn as_str(self) -> &'static str {
+    match self {
+      Purpose::Any => "any",
+      Purpose::Maskable => "maskable",
+      Purpose::Monochrome => "monochrome",
+    }
+  }
+
+  #[inline]
+  fn bit(self) -> u8 {
+    match self {
+      Purpose::Any => 1,
+      Purpose::Maskable => 1 << 1,
+      Purpose::Monochrome => 1 << 2,
+    }
+  }
+}
+
+impl fmt::Display for Purpose {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    f.write_str(self.as_str())
+  }
+}
+
+/// A set of icon purposes, serialized as a space-separated list.
+///
+/// ## Example
+/// ```rust
+/// # extern crate webmanifest;
+/// # use webmanifest::{Purpose, Purposes};
+/// let purposes = Purposes::parse("maskable ANY badge");
+/// assert!(purposes.contains(Purpose::Any));
+/// assert!(purposes.contains(Purpose::Maskable));
+/// assert_eq!(purposes.to_string(), "any maskable");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Purposes {
+  bits: u8,
+}
+
+impl Purposes {
+  /// Create an empty set.
+  #[must_use]
+  #[inline]
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Parse a space-separated list of purposes, ignoring unknown keywords.
+  #[must_use]
+  pub fn parse(value: &str) -> Self {
+    value
+      .split_whitespace()
+      .filter_map(Purpose::parse)
+      .collect()
+  }
+
+  /// Add a purpose to the set.
+  #[must_use]
+  #[inline]
+  pub fn with(mut self, purpose: Purpose) -> Self {
+    self.bits |= purpose.bit();
+    self
+  }
+
+  /// Check if the set contains a purpose.
+  #[must_use]
+  #[inline]
+  pub fn contains(self, purpose: Purpose) -> bool {
+    self.bits & purpose.bit() != 0
+  }
+
+  /// Check if the set is empty.
+  #[must_use]
+  #[inline]
+  pub fn is_empty(self) -> bool {
+    self.bits == 0
+  }
+
+  /// Iterate over the purposes in the set.
+  pub fn iter(self) -> impl Iterator<Item = Purpose> {
+    Purpose::ALL
+      .iter()
+      .cloned()
+      .filter(move |purpose| self.contains(*purpose))
+  }
+}
+
+impl From<Purpose> for Purposes {
+  #[inline]
+  fn from(purpose: Purpose) -> Self {
+    Self::new().with(purpose)
+  }
+}
+
+impl FromIterator<Purpose> for Purposes {
+  fn from_iter<I: IntoIterator<Item = Purpose>>(iter: I) -> Self {
+    iter
+      .into_iter()
+      .fold(Self::new(), |purposes, purpose| purposes.with(purpose))
+  }
+}
+
+impl fmt::Display for Purposes {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    for (index, purpose) in self.iter().enumerate() {
+      if index > 0 {
+        f.write_str(" ")?;
+      }
+      f.write_str(purpose.as_str())?;
+    }
+    Ok(())
+  }
+}
+
+impl Serialize for Purposes {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(self)
+  }
+}
+
+impl<'de> Deserialize<'de> for Purposes {
+  fn deserialize<D: Deserializer<'de>>(
+    deserializer: D,
+  ) -> Result<Self, D::Error> {
+    let value = CowStr::deserialize(deserializer)?;
+    Ok(Self::parse(&value.0))
+  }
+}
@@ -1,3 +1,22 @@
+use serde_json::value::RawValue;
+
+use super::cow;
+use super::process::Processor;
+use super::{Diagnostic, DiagnosticKind};
+
+use std::borrow::Cow;
+
+/// The platforms user agents and app stores know about.
+const PLATFORMS: [&str; 7] = [
+  "amazon",
+  "chrome_web_store",
+  "f-droid",
+  "itunes",
+  "play",
+  "webapp",
+  "windows",
+];
+
 /// An entry in an array of native applications that are installable by, or
 /// accessible to, the underlying platform.
 ///
@@ -6,20 +25,41 @@
 /// provides similar/equivalent functionality — like the native app version of
 /// the website.
 ///
+/// An entry needs a `url`, an `id` or both. `getInstalledRelatedApps()` on
+/// Android matches the `id` and `fingerprints` against installed packages.
+///
 /// ## Example Output
 /// ```json
 /// "related_applications": [{
 ///   "platform": "play",
-///   "url": "https://play.google.com/store/apps/details?id=cheeaun.hackerweb"
+///   "url": "https://play.google.com/store/apps/details?id=cheeaun.hackerweb",
+///   "id": "cheeaun.hackerweb",
+///   "min_version": "2",
+///   "fingerprints": [{
+///     "type": "sha256_cert",
+///     "value": "92:5A:39:05:C5:B9:EA:BC:71:48:5F:F2:05:0A:1E:57:5F:23"
+///   }]
 /// }]
 /// ```
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Related<'s> {
-  platform: &'s str,
-  url: &'s str,
+pub struct Related<'a> {
+  #[serde(borrow)]
+  platform: Cow<'a, str>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  #[serde(borrow, default, deserialize_with = "cow::option")]
+  url: Option<Cow<'a, str>>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  #[serde(borrow, default, deserialize_with = "cow::option")]
+  id: Option<Cow<'a, str>>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  #[serde(borrow, default, deserialize_with = "cow::option")]
+  min_version: Option<Cow<'a, str>>,
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  #[serde(borrow, default)]
+  fingerprints: Vec<Fingerprint<'a>>,
 }
 
-impl<'s> Related<'s> {
+impl<'a> Related<'a> {
   /// Create a new `Related` instance.
   ///
   /// ## Example
@@ -32,7 +72,339 @@ impl<'s> Related<'s> {
   /// ```
   #[inline]
   #[must_use]
-  pub fn new(platform: &'s str, url: &'s str) -> Self {
-    Self { platform, url }
+  pub fn new(
+    platform: impl Into<Cow<'a, str>>,
+    url: impl Into<Cow<'a, str>>,
+  ) -> Self {
+    Self {
+      platform: platform.into(),
+      url: Some(url.into()),
+      id: None,
+      min_version: None,
+      fingerprints: vec![],
+    }
+  }
+
+  /// Create a new `Related` instance without a `url`, identified by its `id`
+  /// on the platform.
+  ///
+  /// ## Example
+  /// ```rust
+  /// # extern crate webmanifest;
+  /// # use webmanifest::{Fingerprint, Related};
+  /// let fingerprint = "92:5A:39:05:C5:B9:EA:BC:71:48:5F:F2:05:0A:1E:57:5F:23";
+  /// let related = Related::with_id("play", "cheeaun.hackerweb")
+  ///   .min_version("2")
+  ///   .fingerprint(Fingerprint::new("sha256_cert", fingerprint));
+  /// assert_eq!(related.get_url(), None);
+  /// ```
+  #[inline]
+  #[must_use]
+  pub fn with_id(
+    platform: impl Into<Cow<'a, str>>,
+    id: impl Into<Cow<'a, str>>,
+  ) -> Self {
+    Self {
+      platform: platform.into(),
+      url: None,
+      id: Some(id.into()),
+      min_version: None,
+      fingerprints: vec![],
+    }
+  }
+
+  /// Set the `id` value, the identifier of the application on the platform.
+  #[must_use]
+  #[inline]
+  pub fn id(mut self, id: impl Into<Cow<'a, str>>) -> Self {
+    self.id = Some(id.into());
+    self
+  }
+
+  /// Set the `min_version` value. An installed application with a lower
+  /// version is not considered related.
+  #[must_use]
+  #[inline]
+  pub fn min_version(mut self, min_version: impl Into<Cow<'a, str>>) -> Self {
+    self.min_version = Some(min_version.into());
+    self
+  }
+
+  /// Add a `Fingerprint` to the fingerprints vector. Accepts a `Fingerprint`
+  /// or a reference to one.
+  #[must_use]
+  #[inline]
+  pub fn fingerprint(
+    mut self,
+    fingerprint: impl Into<Fingerprint<'a>>,
+  ) -> Self {
+    self.fingerprints.push(fingerprint.into());
+    self
+  }
+
+  /// Convert into a related application that owns all of its strings.
+  #[must_use]
+  pub fn into_owned(self) -> Related<'static> {
+    Related {
+      platform: cow::owned(self.platform),
+      url: cow::owned_option(self.url),
+      id: cow::owned_option(self.id),
+      min_version: cow::owned_option(self.min_version),
+      fingerprints: self
+        .fingerprints
+        .into_iter()
+        .map(Fingerprint::into_owned)
+        .collect(),
+    }
+  }
+
+  /// Get the `platform` value.
+  #[must_use]
+  #[inline]
+  pub fn get_platform(&self) -> &str {
+    &self.platform
+  }
+
+  /// Get the `url` value.
+  #[must_use]
+  #[inline]
+  pub fn get_url(&self) -> Option<&str> {
+    self.url.as_deref()
+  }
+
+  /// Get the `id` value.
+  #[must_use]
+  #[inline]
+  pub fn get_id(&self) -> Option<&str> {
+    self.id.as_deref()
+  }
+
+  /// Get the `min_version` value.
+  #[must_use]
+  #[inline]
+  pub fn get_min_version(&self) -> Option<&str> {
+    self.min_version.as_deref()
+  }
+
+  /// Get the `fingerprints` value.
+  #[must_use]
+  #[inline]
+  pub fn get_fingerprints(&self) -> &[Fingerprint<'a>] {
+    &self.fingerprints
+  }
+
+  /// Check if `platform` is one of the platforms user agents know about:
+  /// `play`, `itunes`, `windows`, `webapp`, `chrome_web_store`, `f-droid` or
+  /// `amazon`.
+  #[must_use]
+  pub fn has_known_platform(&self) -> bool {
+    PLATFORMS.contains(&self.platform.as_ref())
+  }
+
+  /// Check if a user agent can use the entry: its `platform` is known, and
+  /// it has a `url` or an `id`.
+  ///
+  /// ## Example
+  /// ```rust
+  /// # extern crate webmanifest;
+  /// # use webmanifest::Related;
+  /// assert!(Related::with_id("play", "cheeaun.hackerweb").is_usable());
+  /// assert!(!Related::with_id("symbian", "hackerweb").is_usable());
+  /// ```
+  #[must_use]
+  pub fn is_usable(&self) -> bool {
+    self.has_known_platform() && (self.url.is_some() || self.id.is_some())
+  }
+
+  /// Process an entry of the `related_applications` member. Entries with
+  /// neither a `url` nor an `id` are skipped.
+  pub(crate) fn process(
+    raw: &'a RawValue,
+    path: &str,
+    diagnostics: &mut Vec<Diagnostic>,
+  ) -> Option<Self> {
+    let mut processor = Processor::object(raw, path, diagnostics)?;
+    let platform = processor.required_text("platform");
+    let url = processor.text("url");
+    let id = processor.text("id");
+    if url.is_none() && id.is_none() {
+      processor.warn("url", DiagnosticKind::MissingMember);
+    }
+    let min_version = processor.text("min_version");
+    let fingerprints = processor.list("fingerprints", Fingerprint::process);
+    processor.finish(diagnostics);
+    if url.is_none() && id.is_none() {
+      return None;
+    }
+    Some(Self {
+      platform: platform?,
+      url,
+      id,
+      min_version,
+      fingerprints,
+    })
+  }
+}
+
+impl<'a, 'b> From<&'b Related<'a>> for Related<'a> {
+  #[inline]
+  fn from(related: &'b Related<'a>) -> Self {
+    related.clone()
+  }
+}
+
+/// The fingerprint of the certificate an application is signed with, used to
+/// verify an installed application is the related one.
+///
+/// ## Example Output
+/// ```json
+/// "fingerprints": [{
+///   "type": "sha256_cert",
+///   "value": "92:5A:39:05:C5:B9:EA:BC:71:48:5F:F2:05:0A:1E:57:5F:23"
+/// }]
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Fingerprint<'a> {
+  #[serde(rename = "type")]
+  #[serde(borrow)]
+  fingerprint_type: Cow<'a, str>,
+  #[serde(borrow)]
+  value: Cow<'a, str>,
+}
+
+impl<'a> Fingerprint<'a> {
+  /// Create a new `Fingerprint` instance, e.g. of type `sha256_cert`.
+  #[must_use]
+  #[inline]
+  pub fn new(
+    fingerprint_type: impl Into<Cow<'a, str>>,
+    value: impl Into<Cow<'a, str>>,
+  ) -> Self {
+    Self {
+      fingerprint_type: fingerprint_type.into(),
+      value: value.into(),
+    }
+  }
+
+  /// Convert into a fingerprint that owns all of its strings.
+  #[must_use]
+  pub fn into_owned(self) -> Fingerprint<'static> {
+    Fingerprint {
+      fingerprint_type: cow::owned(self.fingerprint_type),
+      value: cow::owned(self.value),
+    }
+  }
+
+  /// Get the `type` value.
+  #[must_use]
+  #[inline]
+  pub fn get_fingerprint_type(&self) -> &str {
+    &self.fingerprint_type
+  }
+
+  /// Get the `value` value.
+  #[must_use]
+  #[inline]
+  pub fn get_value(&self) -> &str {
+    &self.value
+  }
+
+  /// Process an entry of the `fingerprints` member.
+  fn process(
+    raw: &'a RawValue,
+    path: &str,
+    diagnostics: &mut Vec<Diagnostic>,
+  ) -> Option<Self> {
+    let mut processor = Processor::object(raw, path, diagnostics)?;
+    let fingerprint_type = processor.required_text("type");
+    let value = processor.required_text("value");
+    processor.finish(diagnostics);
+    Some(Self::new(fingerprint_type?, value?))
+  }
+}
+
+impl<'a, 'b> From<&'b Fingerprint<'a>> for Fingerprint<'a> {
+  #[inline]
+  fn from(fingerprint: &'b Fingerprint<'a>) -> Self {
+    fingerprint.clone()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::super::{Manifest, Severity, Url};
+  use super::*;
+
+  #[test]
+  fn process_drops_entries_without_url_or_id() {
+    let json = r#"{
+      "name": "App",
+      "related_applications": [
+        { "platform": "play" },
+        { "platform": "play", "id": "com.example.app" },
+        { "platform": "itunes", "url": "https://example.com/app" }
+      ]
+    }"#;
+    let (manifest, diagnostics) = Manifest::process(json).unwrap();
+    let related = manifest.get_related();
+    assert_eq!(related.len(), 2);
+    assert_eq!(related[0].get_id(), Some("com.example.app"));
+    assert_eq!(related[1].get_platform(), "itunes");
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].get_member(), "related_applications[0].url");
+    assert_eq!(diagnostics[0].get_kind(), &DiagnosticKind::MissingMember);
+  }
+
+  #[test]
+  fn platform_must_be_known() {
+    for platform in PLATFORMS.iter() {
+      assert!(Related::with_id(*platform, "app").has_known_platform());
+    }
+    for platform in &["Play", "android", "ios", ""] {
+      let related = Related::with_id(*platform, "app");
+      assert!(!related.has_known_platform(), "{}", platform);
+      assert!(!related.is_usable(), "{}", platform);
+    }
+  }
+
+  #[test]
+  fn validate_reports_unusable_entries() {
+    let base_url = Url::parse("https://e.com/manifest.json").unwrap();
+    let json = r#"{
+      "name": "App",
+      "prefer_related_applications": true,
+      "related_applications": [
+        { "platform": "android", "id": "com.example.app" },
+        { "platform": "play" }
+      ]
+    }"#;
+    let manifest = Manifest::parse(json).unwrap();
+    let diagnostics = manifest.validate(&base_url);
+    let found: Vec<_> = diagnostics
+      .iter()
+      .map(|d| (d.get_member(), d.get_kind().clone()))
+      .collect();
+    let unknown = DiagnosticKind::UnknownPlatform {
+      platform: "android".to_string(),
+    };
+    assert_eq!(
+      found,
+      [
+        ("related_applications[0].platform", unknown),
+        ("related_applications[1].url", DiagnosticKind::MissingMember),
+        (
+          "prefer_related_applications",
+          DiagnosticKind::NoUsableRelated
+        ),
+      ]
+    );
+    assert!(diagnostics
+      .iter()
+      .all(|d| d.get_severity() == Severity::Warning));
+
+    let manifest = Manifest::builder("App")
+      .prefer_related_applications(true)
+      .related(Related::with_id("play", "com.example.app"));
+    assert!(manifest.validate(&base_url).is_empty());
   }
 }
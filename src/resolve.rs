@@ -0,0 +1,421 @@
+use url::Url;
+
+use super::{Diagnostic, DiagnosticKind, Manifest};
+
+/// The URL members of a manifest, resolved to absolute URLs.
+///
+/// Created by `Manifest::resolve`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResolvedUrls {
+  id: Url,
+  start_url: Url,
+  scope: Url,
+  icons: Vec<Option<Url>>,
+  screenshots: Vec<Option<Url>>,
+  shortcuts: Vec<Option<Url>>,
+  share_target: Option<Url>,
+  protocol_handlers: Vec<Option<Url>>,
+  file_handlers: Vec<Option<Url>>,
+  related: Vec<Option<Url>>,
+}
+
+impl ResolvedUrls {
+  /// Get the identity of the application. See `Manifest::identity`.
+  #[must_use]
+  #[inline]
+  pub fn get_id(&self) -> &Url {
+    &self.id
+  }
+
+  /// Get the resolved `start_url`.
+  #[must_use]
+  #[inline]
+  pub fn get_start_url(&self) -> &Url {
+    &self.start_url
+  }
+
+  /// Get the resolved `scope`.
+  #[must_use]
+  #[inline]
+  pub fn get_scope(&self) -> &Url {
+    &self.scope
+  }
+
+  /// Get the resolved `src` of every icon, in the same order as
+  /// `Manifest::get_icons`. Icons whose `src` could not be resolved are
+  /// `None`.
+  #[must_use]
+  #[inline]
+  pub fn get_icons(&self) -> &[Option<Url>] {
+    &self.icons
+  }
+
+  /// Get the resolved `src` of every screenshot, in the same order as
+  /// `Manifest::get_screenshots`. Screenshots whose `src` could not be
+  /// resolved are `None`.
+  #[must_use]
+  #[inline]
+  pub fn get_screenshots(&self) -> &[Option<Url>] {
+    &self.screenshots
+  }
+
+  /// Get the resolved `url` of every shortcut, in the same order as
+  /// `Manifest::get_shortcuts`. Shortcuts whose `url` could not be resolved
+  /// are `None`.
+  #[must_use]
+  #[inline]
+  pub fn get_shortcuts(&self) -> &[Option<Url>] {
+    &self.shortcuts
+  }
+
+  /// Get the resolved `action` of the share target. `None` if there is no
+  /// share target or its `action` could not be resolved.
+  #[must_use]
+  #[inline]
+  pub fn get_share_target(&self) -> Option<&Url> {
+    self.share_target.as_ref()
+  }
+
+  /// Get the resolved `url` template of every protocol handler, in the same
+  /// order as `Manifest::get_protocol_handlers`. Handlers whose `url` could
+  /// not be resolved are `None`.
+  #[must_use]
+  #[inline]
+  pub fn get_protocol_handlers(&self) -> &[Option<Url>] {
+    &self.protocol_handlers
+  }
+
+  /// Get the resolved `action` of every file handler, in the same order as
+  /// `Manifest::get_file_handlers`. Handlers whose `action` could not be
+  /// resolved are `None`.
+  #[must_use]
+  #[inline]
+  pub fn get_file_handlers(&self) -> &[Option<Url>] {
+    &self.file_handlers
+  }
+
+  /// Get the resolved `url` of every related application, in the same order
+  /// as `Manifest::get_related`. Entries without a `url`, or whose `url` could
+  /// not be resolved, are `None`.
+  #[must_use]
+  #[inline]
+  pub fn get_related(&self) -> &[Option<Url>] {
+    &self.related
+  }
+}
+
+impl<'a> Manifest<'a> {
+  /// Resolve the URL members against the URL the manifest was fetched from
+  /// and the URL of the document that linked it.
+  ///
+  /// This follows the spec rules: `start_url` defaults to `document_url` and
+  /// must be same-origin with it, `id` resolves against the origin of
+  /// `start_url` and defaults to it, `scope` defaults to the directory of
+  /// `start_url` and must be same-origin with it and contain it, and icon and
+  /// screenshot `src`, shortcut `url`, share target and file handler
+  /// `action`, protocol handler `url` and related application `url` values
+  /// resolve against `manifest_url`. Values that can't be used are reported
+  /// as warnings and replaced by their default.
+  ///
+  /// ## Example
+  /// ```rust
+  /// # extern crate webmanifest;
+  /// # use webmanifest::{Manifest, Url};
+  /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+  /// let manifest_url = Url::parse("https://example.com/app/manifest.json")?;
+  /// let document_url = Url::parse("https://example.com/app/index.html")?;
+  /// let manifest = Manifest::builder("My Cool Application")
+  ///   .start_url("./?source=pwa");
+  /// let (urls, diagnostics) = manifest.resolve(&manifest_url, &document_url);
+  /// let start_url = "https://example.com/app/?source=pwa";
+  /// assert_eq!(urls.get_start_url().as_str(), start_url);
+  /// assert_eq!(urls.get_scope().as_str(), "https://example.com/app/");
+  /// assert!(diagnostics.is_empty());
+  /// # Ok(())}
+  /// ```
+  pub fn resolve(
+    &self,
+    manifest_url: &Url,
+    document_url: &Url,
+  ) -> (ResolvedUrls, Vec<Diagnostic>) {
+    let mut diagnostics = vec![];
+
+    let start_url = self
+      .get_start_url()
+      .and_then(|value| {
+        let url = join(manifest_url, "start_url", value, &mut diagnostics)?;
+        same_origin(document_url, "start_url", url, &mut diagnostics)
+      })
+      .unwrap_or_else(|| document_url.clone());
+
+    let id = identity(self.get_id(), &start_url, &mut diagnostics);
+
+    let scope = self
+      .get_scope()
+      .and_then(|value| {
+        let url = join(manifest_url, "scope", value, &mut diagnostics)?;
+        let url = same_origin(&start_url, "scope", url, &mut diagnostics)?;
+        contains(&url, "scope", &start_url, &mut diagnostics)
+      })
+      .unwrap_or_else(|| default_scope(&start_url));
+
+    let icons = self
+      .icons
+      .iter()
+      .enumerate()
+      .map(|(index, icon)| {
+        let member = format!("icons[{}].src", index);
+        join(manifest_url, &member, icon.get_src(), &mut diagnostics)
+      })
+      .collect();
+
+    let screenshots = self
+      .screenshots
+      .iter()
+      .enumerate()
+      .map(|(index, screenshot)| {
+        let member = format!("screenshots[{}].src", index);
+        join(
+          manifest_url,
+          &member,
+          screenshot.get_src(),
+          &mut diagnostics,
+        )
+      })
+      .collect();
+
+    let shortcuts = self
+      .shortcuts
+      .iter()
+      .enumerate()
+      .map(|(index, shortcut)| {
+        let member = format!("shortcuts[{}].url", index);
+        join(manifest_url, &member, shortcut.get_url(), &mut diagnostics)
+      })
+      .collect();
+
+    let share_target = self.share_target.as_ref().and_then(|share_target| {
+      let member = "share_target.action";
+      join(
+        manifest_url,
+        member,
+        share_target.get_action(),
+        &mut diagnostics,
+      )
+    });
+
+    let protocol_handlers = self
+      .protocol_handlers
+      .iter()
+      .enumerate()
+      .map(|(index, handler)| {
+        let member = format!("protocol_handlers[{}].url", index);
+        join(manifest_url, &member, handler.get_url(), &mut diagnostics)
+      })
+      .collect();
+
+    let file_handlers = self
+      .file_handlers
+      .iter()
+      .enumerate()
+      .map(|(index, handler)| {
+        let member = format!("file_handlers[{}].action", index);
+        join(
+          manifest_url,
+          &member,
+          handler.get_action(),
+          &mut diagnostics,
+        )
+      })
+      .collect();
+
+    let related = self
+      .related_applications
+      .iter()
+      .enumerate()
+      .map(|(index, related)| {
+        let member = format!("related_applications[{}].url", index);
+        let url = related.get_url()?;
+        join(manifest_url, &member, url, &mut diagnostics)
+      })
+      .collect();
+
+    let urls = ResolvedUrls {
+      id,
+      start_url,
+      scope,
+      icons,
+      screenshots,
+      shortcuts,
+      share_target,
+      protocol_handlers,
+      file_handlers,
+      related,
+    };
+    (urls, diagnostics)
+  }
+}
+
+impl<'a> Manifest<'a> {
+  /// Compute the identity of the application the manifest describes.
+  ///
+  /// User agents treat two manifests with the same identity as the same
+  /// installed application. The identity is `id` resolved against the origin of
+  /// `start_url`, with its fragment removed. If `id` is missing, empty, invalid
+  /// or cross-origin, the identity is `start_url` without its fragment. A
+  /// change of identity between deploys orphans existing installs.
+  ///
+  /// ## Example
+  /// ```rust
+  /// # extern crate webmanifest;
+  /// # use webmanifest::{Manifest, Url};
+  /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+  /// let manifest_url = Url::parse("https://example.com/manifest.json")?;
+  /// let document_url = Url::parse("https://example.com/")?;
+  /// let old = Manifest::builder("My Cool Application").start_url("/v1/");
+  /// let new = Manifest::builder("My Cool Application").start_url("/v2/");
+  /// assert_ne!(
+  ///   old.identity(&manifest_url, &document_url),
+  ///   new.identity(&manifest_url, &document_url),
+  /// );
+  ///
+  /// let new = new.id("/v1/");
+  /// assert_eq!(
+  ///   old.identity(&manifest_url, &document_url),
+  ///   new.identity(&manifest_url, &document_url),
+  /// );
+  /// # Ok(())}
+  /// ```
+  pub fn identity(&self, manifest_url: &Url, document_url: &Url) -> Url {
+    let (urls, _) = self.resolve(manifest_url, document_url);
+    urls.id
+  }
+}
+
+/// Compute the identity from `id` and the resolved `start_url`.
+fn identity(
+  id: Option<&str>,
+  start_url: &Url,
+  diagnostics: &mut Vec<Diagnostic>,
+) -> Url {
+  let origin = start_url.join("/").ok();
+  let url = match (id, origin) {
+    (Some(id), Some(origin)) if !id.is_empty() => {
+      join(&origin, "id", id, diagnostics)
+        .and_then(|url| same_origin(start_url, "id", url, diagnostics))
+    }
+    _ => None,
+  };
+  let mut url = url.unwrap_or_else(|| start_url.clone());
+  url.set_fragment(None);
+  url
+}
+
+/// Resolve `value` against `base`, recording a warning if it can't be parsed.
+pub(crate) fn join(
+  base: &Url,
+  member: &str,
+  value: &str,
+  diagnostics: &mut Vec<Diagnostic>,
+) -> Option<Url> {
+  match base.join(value) {
+    Ok(url) => Some(url),
+    Err(_) => {
+      let kind = DiagnosticKind::InvalidUrl {
+        value: value.to_string(),
+      };
+      diagnostics.push(Diagnostic::warning(member, kind));
+      None
+    }
+  }
+}
+
+/// Check that `url` has the same origin as `other`, recording a warning if it
+/// doesn't.
+fn same_origin(
+  other: &Url,
+  member: &str,
+  url: Url,
+  diagnostics: &mut Vec<Diagnostic>,
+) -> Option<Url> {
+  if url.origin() == other.origin() {
+    return Some(url);
+  }
+  let kind = DiagnosticKind::CrossOrigin {
+    url: url.to_string(),
+    origin: other.origin().ascii_serialization(),
+  };
+  diagnostics.push(Diagnostic::warning(member, kind));
+  None
+}
+
+/// Check that `url` is within `scope`, recording a warning if it isn't.
+fn contains(
+  scope: &Url,
+  member: &str,
+  url: &Url,
+  diagnostics: &mut Vec<Diagnostic>,
+) -> Option<Url> {
+  if within_scope(url, scope) {
+    return Some(scope.clone());
+  }
+  let kind = DiagnosticKind::OutOfScope {
+    url: url.to_string(),
+    scope: scope.to_string(),
+  };
+  diagnostics.push(Diagnostic::warning(member, kind));
+  None
+}
+
+/// The default scope: `start_url` with its filename, query and fragment
+/// removed.
+pub(crate) fn default_scope(start_url: &Url) -> Url {
+  let mut scope = start_url.clone();
+  scope.set_query(None);
+  scope.set_fragment(None);
+  if let Ok(mut segments) = scope.path_segments_mut() {
+    segments.pop().push("");
+  }
+  scope
+}
+
+/// Check if `url` is within `scope`: same-origin, with a path that starts with
+/// the path of `scope`. The query and fragment of `scope` are ignored.
+pub(crate) fn within_scope(url: &Url, scope: &Url) -> bool {
+  url.origin() == scope.origin() && url.path().starts_with(scope.path())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn scope_without_start_url_falls_back_to_default() {
+    let manifest_url = Url::parse("https://e.com/manifest.json").unwrap();
+    let document_url = Url::parse("https://e.com/").unwrap();
+    let manifest = Manifest::builder("App")
+      .start_url("/index.html")
+      .scope("/app/");
+    let (urls, diagnostics) = manifest.resolve(&manifest_url, &document_url);
+    assert_eq!(urls.get_scope().as_str(), "https://e.com/");
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].get_member(), "scope");
+    let kind = DiagnosticKind::OutOfScope {
+      url: "https://e.com/index.html".to_string(),
+      scope: "https://e.com/app/".to_string(),
+    };
+    assert_eq!(diagnostics[0].get_kind(), &kind);
+  }
+
+  #[test]
+  fn scope_with_start_url_is_kept() {
+    let manifest_url = Url::parse("https://e.com/manifest.json").unwrap();
+    let document_url = Url::parse("https://e.com/").unwrap();
+    let manifest = Manifest::builder("App")
+      .start_url("/app/index.html")
+      .scope("/app/");
+    let (urls, diagnostics) = manifest.resolve(&manifest_url, &document_url);
+    assert_eq!(urls.get_scope().as_str(), "https://e.com/app/");
+    assert!(diagnostics.is_empty());
+  }
+}
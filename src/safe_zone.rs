@@ -0,0 +1,315 @@
+use image::{ImageError, Rgba, RgbaImage};
+
+use std::collections::HashMap;
+use std::io;
+use std::path::Path;
+
+use super::assets::local_path;
+use super::generate::SAFE_ZONE;
+use super::{Diagnostic, DiagnosticKind, Error, Manifest, Purpose};
+
+/// How far a color channel may be from the background color before a pixel
+/// counts as content.
+const TOLERANCE: u8 = 16;
+
+/// The result of checking a maskable icon against the safe zone. Created by
+/// `Manifest::check_safe_zone`.
+///
+/// Requires the `image` feature.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SafeZoneReport {
+  member: String,
+  src: String,
+  outside: u64,
+  offending: u64,
+  transparent_edges: bool,
+}
+
+impl SafeZoneReport {
+  /// Get the member of the icon, e.g. `icons[2]`.
+  #[must_use]
+  #[inline]
+  pub fn get_member(&self) -> &str {
+    &self.member
+  }
+
+  /// Get the `src` of the icon.
+  #[must_use]
+  #[inline]
+  pub fn get_src(&self) -> &str {
+    &self.src
+  }
+
+  /// Get the number of content pixels outside the safe zone.
+  #[must_use]
+  #[inline]
+  pub fn get_offending_pixels(&self) -> u64 {
+    self.offending
+  }
+
+  /// Get the share of the pixels outside the safe zone that are content, from
+  /// 0 to 100.
+  #[must_use]
+  pub fn get_percentage(&self) -> f64 {
+    if self.outside == 0 {
+      0.0
+    } else {
+      self.offending as f64 * 100.0 / self.outside as f64
+    }
+  }
+
+  /// Check if any pixel on the edges of the icon is not fully opaque.
+  #[must_use]
+  #[inline]
+  pub fn has_transparent_edges(&self) -> bool {
+    self.transparent_edges
+  }
+
+  /// Get the problems found, as diagnostics on the icon's `src`. Transparent
+  /// edges are errors, content outside the safe zone is a warning.
+  #[must_use]
+  pub fn get_diagnostics(&self) -> Vec<Diagnostic> {
+    let member = format!("{}.src", self.member);
+    let mut diagnostics = vec![];
+    if self.transparent_edges {
+      let kind = DiagnosticKind::TransparentEdges;
+      diagnostics.push(Diagnostic::error(member.clone(), kind));
+    }
+    if self.offending > 0 {
+      let kind = DiagnosticKind::OutsideSafeZone {
+        percentage: self.get_percentage(),
+      };
+      diagnostics.push(Diagnostic::warning(member, kind));
+    }
+    diagnostics
+  }
+}
+
+impl<'a> Manifest<'a> {
+  /// Check every icon with the `maskable` purpose against the safe zone: the
+  /// central circle with a diameter of 80% of the icon, which launchers never
+  /// crop.
+  ///
+  /// Requires the `image` feature.
+  ///
+  /// The background color is the most common opaque color on the edges of the
+  /// icon. Pixels outside the safe zone that are opaque and differ from the
+  /// background are counted as content that may be cropped. Maskable icons
+  /// must be fully opaque, so any transparency on the edges is reported too.
+  ///
+  /// Icon `src`s are resolved into `dir` like `Manifest::check_assets` does.
+  /// Files that are missing or can't be decoded (including SVGs) are skipped;
+  /// `Manifest::check_assets` reports those.
+  ///
+  /// ## Errors
+  /// Fails if a file exists but can't be read.
+  ///
+  /// ## Example
+  /// ```rust,no_run
+  /// # extern crate webmanifest;
+  /// # use webmanifest::{Icon, Manifest, Purpose};
+  /// # fn main() -> Result<(), webmanifest::Error> {
+  /// let manifest = Manifest::builder("My Cool Application").icon(
+  ///   Icon::new("icons/maskable-192.png", (192, 192)).purpose(Purpose::Maskable),
+  /// );
+  /// for report in manifest.check_safe_zone("public")? {
+  ///   assert!(!report.has_transparent_edges());
+  ///   let src = report.get_src();
+  ///   assert!(report.get_percentage() < 1.0, "{} is cropped", src);
+  /// }
+  /// # Ok(())}
+  /// ```
+  pub fn check_safe_zone(
+    &self,
+    dir: impl AsRef<Path>,
+  ) -> Result<Vec<SafeZoneReport>, Error> {
+    let mut reports = vec![];
+    for (index, icon) in self.icons.iter().enumerate() {
+      if !icon.get_purposes().contains(Purpose::Maskable) {
+        continue;
+      }
+      let path = match local_path(dir.as_ref(), icon.get_src()) {
+        Ok(Some(path)) => path,
+        _ => continue,
+      };
+      let image = match open_image(&path)? {
+        Some(image) => image,
+        None => continue,
+      };
+      let (outside, offending) = measure(&image);
+      reports.push(SafeZoneReport {
+        member: format!("icons[{}]", index),
+        src: icon.get_src().to_string(),
+        outside,
+        offending,
+        transparent_edges: edges(&image).any(|pixel| pixel[3] < 255),
+      });
+    }
+    Ok(reports)
+  }
+}
+
+/// Open and decode an image. Returns `None` if the file is missing or can't
+/// be decoded.
+pub(crate) fn open_image(path: &Path) -> Result<Option<RgbaImage>, Error> {
+  match image::open(path) {
+    Ok(image) => Ok(Some(image.to_rgba8())),
+    Err(ImageError::IoError(ref err))
+      if err.kind() == io::ErrorKind::NotFound =>
+    {
+      Ok(None)
+    }
+    Err(ImageError::IoError(err)) => Err(Error::Io(err)),
+    Err(_) => Ok(None),
+  }
+}
+
+/// Count the pixels outside the safe zone, and how many of them are content.
+fn measure(image: &RgbaImage) -> (u64, u64) {
+  let background = background(image);
+  let center_x = f64::from(image.width()) / 2.0;
+  let center_y = f64::from(image.height()) / 2.0;
+  let radius = f64::from(image.width().min(image.height())) * SAFE_ZONE / 2.0;
+
+  let mut outside = 0;
+  let mut offending = 0;
+  for (x, y, pixel) in image.enumerate_pixels() {
+    let dx = f64::from(x) + 0.5 - center_x;
+    let dy = f64::from(y) + 0.5 - center_y;
+    if dx * dx + dy * dy <= radius * radius {
+      continue;
+    }
+    outside += 1;
+    let content = pixel[3] == 255
+      && background.is_none_or(|background| !similar(*pixel, background));
+    if content {
+      offending += 1;
+    }
+  }
+  (outside, offending)
+}
+
+/// Find the most common opaque color on the edges of the image.
+fn background(image: &RgbaImage) -> Option<Rgba<u8>> {
+  let mut counts = HashMap::new();
+  for pixel in edges(image).filter(|pixel| pixel[3] == 255) {
+    *counts.entry(pixel.0).or_insert(0) += 1;
+  }
+  counts
+    .into_iter()
+    .max_by_key(|&(color, count)| (count, color))
+    .map(|(color, _)| Rgba(color))
+}
+
+/// Iterate over the pixels on the edges of the image.
+fn edges(image: &RgbaImage) -> impl Iterator<Item = &Rgba<u8>> {
+  let (width, height) = image.dimensions();
+  image.enumerate_pixels().filter_map(move |(x, y, pixel)| {
+    let edge = x == 0 || y == 0 || x + 1 == width || y + 1 == height;
+    if edge {
+      Some(pixel)
+    } else {
+      None
+    }
+  })
+}
+
+/// Check if two colors are within `TOLERANCE` on every channel.
+fn similar(a: Rgba<u8>, b: Rgba<u8>) -> bool {
+  a.0
+    .iter()
+    .zip(b.0.iter())
+    .all(|(a, b)| (i16::from(*a) - i16::from(*b)).abs() <= i16::from(TOLERANCE))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::super::test_util::temp_dir;
+  use super::super::{Icon, IconGenerator};
+  use super::*;
+
+  use std::fs;
+
+  fn maskable(src: &str) -> Icon<'_> {
+    Icon::new(src, (100, 100)).purpose(Purpose::Maskable)
+  }
+
+  #[test]
+  fn generated_maskable_icon_passes() {
+    let dir = temp_dir("safe-zone-generated");
+    let source = RgbaImage::from_pixel(10, 10, Rgba([255, 0, 0, 255]));
+    source.save(dir.join("source.png")).unwrap();
+    let icons = IconGenerator::new(dir.join("source.png"))
+      .size(100)
+      .purpose(Purpose::Maskable)
+      .generate(&dir)
+      .unwrap();
+    let manifest = Manifest::builder("App").icon(&icons[0]);
+    let reports = manifest.check_safe_zone(&dir).unwrap();
+    assert_eq!(reports.len(), 1);
+    assert_eq!(reports[0].get_member(), "icons[0]");
+    assert_eq!(reports[0].get_src(), "icon-maskable-100.png");
+    assert!(!reports[0].has_transparent_edges());
+    assert_eq!(reports[0].get_offending_pixels(), 0);
+    assert!(reports[0].get_diagnostics().is_empty());
+    fs::remove_dir_all(dir).unwrap();
+  }
+
+  #[test]
+  fn reports_content_outside_safe_zone() {
+    let dir = temp_dir("safe-zone-content");
+    let mut image = RgbaImage::from_pixel(100, 100, Rgba([255, 255, 255, 255]));
+    for x in 5..15 {
+      for y in 5..15 {
+        image.put_pixel(x, y, Rgba([0, 0, 0, 255]));
+      }
+    }
+    image.save(dir.join("icon.png")).unwrap();
+    let manifest = Manifest::builder("App").icon(maskable("icon.png"));
+    let reports = manifest.check_safe_zone(&dir).unwrap();
+    assert_eq!(reports[0].get_offending_pixels(), 100);
+    assert!(reports[0].get_percentage() > 0.0);
+    let diagnostics = reports[0].get_diagnostics();
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].get_member(), "icons[0].src");
+    match diagnostics[0].get_kind() {
+      DiagnosticKind::OutsideSafeZone { percentage } => {
+        assert_eq!(*percentage, reports[0].get_percentage());
+      }
+      kind => panic!("unexpected {:?}", kind),
+    }
+    fs::remove_dir_all(dir).unwrap();
+  }
+
+  #[test]
+  fn reports_transparent_edges() {
+    let dir = temp_dir("safe-zone-transparent");
+    let mut image = RgbaImage::from_pixel(100, 100, Rgba([0, 0, 0, 0]));
+    image.put_pixel(50, 50, Rgba([255, 0, 0, 255]));
+    image.save(dir.join("icon.png")).unwrap();
+    let manifest = Manifest::builder("App").icon(maskable("icon.png"));
+    let reports = manifest.check_safe_zone(&dir).unwrap();
+    assert!(reports[0].has_transparent_edges());
+    assert_eq!(reports[0].get_offending_pixels(), 0);
+    let diagnostics = reports[0].get_diagnostics();
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].get_kind(), &DiagnosticKind::TransparentEdges);
+    fs::remove_dir_all(dir).unwrap();
+  }
+
+  #[test]
+  fn skips_icons_that_cannot_be_checked() {
+    let dir = temp_dir("safe-zone-skipped");
+    let image = RgbaImage::from_pixel(100, 100, Rgba([0, 0, 0, 0]));
+    image.save(dir.join("icon.png")).unwrap();
+    fs::write(dir.join("icon.svg"), "<svg></svg>").unwrap();
+    let manifest = Manifest::builder("App")
+      .icon(Icon::new("icon.png", (100, 100)))
+      .icon(maskable("missing.png"))
+      .icon(maskable("icon.svg"))
+      .icon(maskable("/%2Fetc%2Fpasswd"))
+      .icon(maskable("https://example.com/icon.png"));
+    assert!(manifest.check_safe_zone(&dir).unwrap().is_empty());
+    fs::remove_dir_all(dir).unwrap();
+  }
+}
@@ -0,0 +1,195 @@
+use mime_guess;
+use serde_json::value::RawValue;
+
+use super::cow;
+use super::process::Processor;
+use super::{Diagnostic, FormFactor, Sizes};
+
+use std::borrow::Cow;
+
+/// Add a screenshot of the application to the web manifest. App stores and
+/// install dialogs show screenshots to describe the application.
+///
+/// ## Example Output
+/// ```json
+/// "screenshots": [{
+///   "src": "screenshots/desktop.png",
+///   "sizes": "1280x720",
+///   "type": "image/png",
+///   "form_factor": "wide",
+///   "label": "Homescreen of My Cool Application"
+/// }, {
+///   "src": "screenshots/mobile.png",
+///   "sizes": "720x1280",
+///   "type": "image/png",
+///   "form_factor": "narrow"
+/// }]
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Screenshot<'a> {
+  #[serde(borrow)]
+  src: Cow<'a, str>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  sizes: Option<Sizes>,
+  #[serde(rename = "type")]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  #[serde(borrow, default, deserialize_with = "cow::option")]
+  screenshot_type: Option<Cow<'a, str>>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  form_factor: Option<FormFactor>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  #[serde(borrow, default, deserialize_with = "cow::option")]
+  label: Option<Cow<'a, str>>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  #[serde(borrow, default, deserialize_with = "cow::option")]
+  platform: Option<Cow<'a, str>>,
+}
+
+impl<'a> Screenshot<'a> {
+  /// Create a new `Screenshot` instance.
+  ///
+  /// `sizes` can be a `(width, height)` tuple, a `Size`, or a `Sizes` list.
+  ///
+  /// ## Example
+  /// ```rust
+  /// # extern crate webmanifest;
+  /// # use webmanifest::Screenshot;
+  /// let screenshot = Screenshot::new("screenshots/desktop.png", (1280, 720));
+  /// assert_eq!(screenshot.get_screenshot_type(), Some("image/png"));
+  /// ```
+  #[must_use]
+  #[inline]
+  pub fn new(src: impl Into<Cow<'a, str>>, sizes: impl Into<Sizes>) -> Self {
+    let src = src.into();
+    let screenshot_type =
+      mime_guess::from_path(src.as_ref()).first_or_octet_stream();
+    Self {
+      src,
+      sizes: Some(sizes.into()).filter(|sizes| !sizes.is_empty()),
+      screenshot_type: Some(Cow::Owned(screenshot_type.to_string())),
+      form_factor: None,
+      label: None,
+      platform: None,
+    }
+  }
+
+  /// Set the `form_factor` value.
+  #[must_use]
+  #[inline]
+  pub fn form_factor(mut self, form_factor: FormFactor) -> Self {
+    self.form_factor = Some(form_factor);
+    self
+  }
+
+  /// Set the `label` value: an accessible description of the screenshot.
+  ///
+  /// ## Example
+  /// ```rust
+  /// # extern crate webmanifest;
+  /// # use webmanifest::{FormFactor, Screenshot};
+  /// let screenshot = Screenshot::new("screenshots/mobile.png", (720, 1280))
+  ///   .form_factor(FormFactor::Narrow)
+  ///   .label("Homescreen of My Cool Application");
+  /// ```
+  #[must_use]
+  #[inline]
+  pub fn label(mut self, label: impl Into<Cow<'a, str>>) -> Self {
+    self.label = Some(label.into());
+    self
+  }
+
+  /// Set the `platform` value: the platform the screenshot applies to, e.g.
+  /// `play` or `windows`.
+  #[must_use]
+  #[inline]
+  pub fn platform(mut self, platform: impl Into<Cow<'a, str>>) -> Self {
+    self.platform = Some(platform.into());
+    self
+  }
+
+  /// Convert into a screenshot that owns all of its strings.
+  #[must_use]
+  pub fn into_owned(self) -> Screenshot<'static> {
+    Screenshot {
+      src: cow::owned(self.src),
+      sizes: self.sizes,
+      screenshot_type: cow::owned_option(self.screenshot_type),
+      form_factor: self.form_factor,
+      label: cow::owned_option(self.label),
+      platform: cow::owned_option(self.platform),
+    }
+  }
+
+  /// Get the `src` value.
+  #[must_use]
+  #[inline]
+  pub fn get_src(&self) -> &str {
+    &self.src
+  }
+
+  /// Get the `sizes` value.
+  #[must_use]
+  #[inline]
+  pub fn get_sizes(&self) -> Option<&Sizes> {
+    self.sizes.as_ref()
+  }
+
+  /// Get the `type` value.
+  #[must_use]
+  #[inline]
+  pub fn get_screenshot_type(&self) -> Option<&str> {
+    self.screenshot_type.as_deref()
+  }
+
+  /// Get the `form_factor` value.
+  #[must_use]
+  #[inline]
+  pub fn get_form_factor(&self) -> Option<FormFactor> {
+    self.form_factor
+  }
+
+  /// Get the `label` value.
+  #[must_use]
+  #[inline]
+  pub fn get_label(&self) -> Option<&str> {
+    self.label.as_deref()
+  }
+
+  /// Get the `platform` value.
+  #[must_use]
+  #[inline]
+  pub fn get_platform(&self) -> Option<&str> {
+    self.platform.as_deref()
+  }
+
+  /// Process an entry of the `screenshots` member.
+  pub(crate) fn process(
+    raw: &'a RawValue,
+    path: &str,
+    diagnostics: &mut Vec<Diagnostic>,
+  ) -> Option<Self> {
+    let mut processor = Processor::object(raw, path, diagnostics)?;
+    let src = processor.required_text("src");
+    let sizes = processor.sizes("sizes");
+    let screenshot_type = processor.text("type");
+    let form_factor = processor.keyword("form_factor");
+    let label = processor.text("label");
+    let platform = processor.text("platform");
+    processor.finish(diagnostics);
+    Some(Self {
+      src: src?,
+      sizes,
+      screenshot_type,
+      form_factor,
+      label,
+      platform,
+    })
+  }
+}
+
+impl<'a, 'b> From<&'b Screenshot<'a>> for Screenshot<'a> {
+  #[inline]
+  fn from(screenshot: &'b Screenshot<'a>) -> Self {
+    screenshot.clone()
+  }
+}
@@ -0,0 +1,327 @@
+use mime_guess;
+
+use super::{Diagnostic, DiagnosticKind, Error, Icon, Manifest, Purpose, Size};
+
+/// The slot an icon is needed for. Used by `Manifest::select_icon`.
+///
+/// ## Example
+/// ```rust
+/// # extern crate webmanifest;
+/// # use webmanifest::{IconQuery, Purpose};
+/// # fn main() -> Result<(), webmanifest::Error> {
+/// let query = IconQuery::new(96)
+///   .density(2.0)?
+///   .purpose(Purpose::Maskable)
+///   .mime_type("image/png");
+/// # Ok(())}
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct IconQuery {
+  size: u32,
+  density: f64,
+  purpose: Purpose,
+  mime_types: Vec<String>,
+}
+
+impl IconQuery {
+  /// Create a new query for a square slot of `size` CSS pixels, at a density
+  /// of 1, for `Purpose::Any`, accepting every MIME type.
+  #[must_use]
+  #[inline]
+  pub fn new(size: u32) -> Self {
+    Self {
+      size,
+      density: 1.0,
+      purpose: Purpose::Any,
+      mime_types: vec![],
+    }
+  }
+
+  /// Set the device pixel ratio.
+  ///
+  /// ## Errors
+  /// Fails if `density` is not a finite number greater than 0.
+  #[inline]
+  pub fn density(mut self, density: f64) -> Result<Self, Error> {
+    if !density.is_finite() || density <= 0.0 {
+      let kind = DiagnosticKind::InvalidValue {
+        value: density.to_string(),
+      };
+      return Err(Error::InvalidValue(Diagnostic::error("density", kind)));
+    }
+    self.density = density;
+    Ok(self)
+  }
+
+  /// Set the purpose the icon must have.
+  #[must_use]
+  #[inline]
+  pub fn purpose(mut self, purpose: Purpose) -> Self {
+    self.purpose = purpose;
+    self
+  }
+
+  /// Add a supported MIME type. If none are added, every type is supported.
+  #[must_use]
+  #[inline]
+  pub fn mime_type(mut self, mime_type: impl Into<String>) -> Self {
+    self.mime_types.push(mime_type.into());
+    self
+  }
+
+  /// Get the size of the slot in device pixels.
+  #[must_use]
+  #[inline]
+  pub fn pixels(&self) -> u32 {
+    (f64::from(self.size) * self.density).ceil() as u32
+  }
+
+  /// Check if the query accepts the type of `icon`. Icons without a `type`
+  /// are matched by the extension of their `src`, and accepted if it is
+  /// unknown.
+  fn accepts(&self, icon: &Icon) -> bool {
+    if self.mime_types.is_empty() {
+      return true;
+    }
+    let mime_type = match icon.get_icon_type() {
+      Some(mime_type) => mime_type.to_string(),
+      None => match mime_guess::from_path(icon.get_src()).first() {
+        Some(mime_type) => mime_type.to_string(),
+        None => return true,
+      },
+    };
+    self
+      .mime_types
+      .iter()
+      .any(|accepted| accepted.eq_ignore_ascii_case(mime_type.trim()))
+  }
+}
+
+impl<'a> Manifest<'a> {
+  /// Select the icon a user agent would use for a slot.
+  ///
+  /// Only icons with the requested purpose and a supported type are
+  /// considered. Icon `sizes` are compared against the slot size in device
+  /// pixels, in this order:
+  /// 1. an exact match,
+  /// 2. the smallest icon larger than the slot,
+  /// 3. an icon declared with the `any` size, preferring SVG,
+  /// 4. the largest icon smaller than the slot,
+  /// 5. an icon without `sizes`.
+  ///
+  /// Only square sizes are considered. Ties go to the icon listed first.
+  ///
+  /// ## Example
+  /// ```rust
+  /// # extern crate webmanifest;
+  /// # use webmanifest::{Icon, IconQuery, Manifest};
+  /// # fn main() -> Result<(), webmanifest::Error> {
+  /// let manifest = Manifest::builder("My Cool Application")
+  ///   .icon(Icon::new("icon-96.png", (96, 96)))
+  ///   .icon(Icon::new("icon-192.png", (192, 192)))
+  ///   .icon(Icon::new("icon-512.png", (512, 512)));
+  /// let icon = manifest.select_icon(&IconQuery::new(96).density(1.5)?);
+  /// assert_eq!(icon.map(|icon| icon.get_src()), Some("icon-192.png"));
+  /// # Ok(())}
+  /// ```
+  #[must_use]
+  pub fn select_icon(&self, query: &IconQuery) -> Option<&Icon<'a>> {
+    let target = query.pixels();
+    let candidates: Vec<&Icon<'a>> = self
+      .icons
+      .iter()
+      .filter(|icon| icon.get_purposes().contains(query.purpose))
+      .filter(|icon| query.accepts(icon))
+      .collect();
+
+    let square = |icon: &Icon| -> Vec<u32> {
+      icon
+        .get_sizes()
+        .map(|sizes| {
+          sizes
+            .iter()
+            .filter_map(|size| match *size {
+              Size::Exact { width, height } if width == height => Some(width),
+              _ => None,
+            })
+            .collect()
+        })
+        .unwrap_or_default()
+    };
+
+    let exact = candidates
+      .iter()
+      .find(|icon| square(icon).contains(&target));
+    if let Some(icon) = exact {
+      return Some(icon);
+    }
+
+    let larger = candidates
+      .iter()
+      .filter_map(|icon| {
+        let size = square(icon).into_iter().filter(|s| *s > target).min()?;
+        Some((size, *icon))
+      })
+      .min_by_key(|(size, _)| *size);
+    if let Some((_, icon)) = larger {
+      return Some(icon);
+    }
+
+    let any: Vec<&Icon<'a>> = candidates
+      .iter()
+      .filter(|icon| icon.get_sizes().is_some_and(|sizes| sizes.is_any()))
+      .cloned()
+      .collect();
+    let svg = any.iter().find(|icon| is_svg(icon));
+    if let Some(icon) = svg.or_else(|| any.first()) {
+      return Some(icon);
+    }
+
+    let smaller = candidates
+      .iter()
+      .filter_map(|icon| {
+        let size = square(icon).into_iter().filter(|s| *s < target).max()?;
+        Some((size, *icon))
+      })
+      .fold(
+        None,
+        |best: Option<(u32, &Icon<'a>)>, candidate| match best {
+          Some(best) if best.0 >= candidate.0 => Some(best),
+          _ => Some(candidate),
+        },
+      );
+    if let Some((_, icon)) = smaller {
+      return Some(icon);
+    }
+
+    candidates
+      .into_iter()
+      .find(|icon| icon.get_sizes().is_none())
+  }
+}
+
+/// Check if an icon is an SVG, by its `type` or the extension of its `src`.
+fn is_svg(icon: &Icon) -> bool {
+  match icon.get_icon_type() {
+    Some(mime_type) => mime_type.trim().eq_ignore_ascii_case("image/svg+xml"),
+    None => mime_guess::from_path(icon.get_src())
+      .first()
+      .is_some_and(|mime_type| mime_type == "image/svg+xml"),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::super::Purposes;
+  use super::*;
+
+  fn select<'a>(manifest: &'a Manifest, query: &IconQuery) -> Option<&'a str> {
+    manifest.select_icon(query).map(|icon| icon.get_src())
+  }
+
+  fn sized(src: &str, size: u32) -> Icon<'_> {
+    Icon::new(src, (size, size))
+  }
+
+  fn any(src: &str) -> Icon<'_> {
+    Icon::new(src, Size::Any)
+  }
+
+  #[test]
+  fn density_must_be_finite_and_positive() {
+    for density in &[0.0, -1.0, f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+      assert!(IconQuery::new(48).density(*density).is_err(), "{}", density);
+    }
+    let query = IconQuery::new(48).density(1.5).unwrap();
+    assert_eq!(query.pixels(), 72);
+    let query = IconQuery::new(48).density(1.01).unwrap();
+    assert_eq!(query.pixels(), 49);
+  }
+
+  #[test]
+  fn prefers_exact_match() {
+    let manifest = Manifest::builder("App")
+      .icon(sized("48.png", 48))
+      .icon(sized("96.png", 96))
+      .icon(any("any.svg"));
+    assert_eq!(select(&manifest, &IconQuery::new(96)), Some("96.png"));
+  }
+
+  #[test]
+  fn then_smallest_larger() {
+    let manifest = Manifest::builder("App")
+      .icon(sized("512.png", 512))
+      .icon(sized("48.png", 48))
+      .icon(sized("192.png", 192))
+      .icon(any("any.svg"));
+    assert_eq!(select(&manifest, &IconQuery::new(96)), Some("192.png"));
+  }
+
+  #[test]
+  fn then_any_preferring_svg() {
+    let manifest = Manifest::builder("App")
+      .icon(sized("48.png", 48))
+      .icon(any("any.png"))
+      .icon(any("any.svg"));
+    assert_eq!(select(&manifest, &IconQuery::new(96)), Some("any.svg"));
+
+    let manifest = Manifest::builder("App")
+      .icon(sized("48.png", 48))
+      .icon(any("any.png"));
+    assert_eq!(select(&manifest, &IconQuery::new(96)), Some("any.png"));
+  }
+
+  #[test]
+  fn then_largest_smaller() {
+    let manifest = Manifest::builder("App")
+      .icon(sized("32.png", 32))
+      .icon(sized("64.png", 64))
+      .icon(sized("48.png", 48));
+    assert_eq!(select(&manifest, &IconQuery::new(96)), Some("64.png"));
+  }
+
+  #[test]
+  fn then_icon_without_sizes() {
+    let json = r#"{
+      "name": "App",
+      "icons": [
+        { "src": "wide.png", "sizes": "96x48" },
+        { "src": "unsized.png" }
+      ]
+    }"#;
+    let manifest = Manifest::parse(json).unwrap();
+    assert_eq!(select(&manifest, &IconQuery::new(96)), Some("unsized.png"));
+    assert_eq!(select(&Manifest::builder("App"), &IconQuery::new(96)), None);
+  }
+
+  #[test]
+  fn ties_go_to_the_first_icon() {
+    let manifest = Manifest::builder("App")
+      .icon(sized("a.png", 192))
+      .icon(sized("b.png", 192))
+      .icon(sized("c.png", 48))
+      .icon(sized("d.png", 48));
+    assert_eq!(select(&manifest, &IconQuery::new(192)), Some("a.png"));
+    assert_eq!(select(&manifest, &IconQuery::new(96)), Some("a.png"));
+    assert_eq!(select(&manifest, &IconQuery::new(512)), Some("a.png"));
+    let manifest = Manifest::builder("App")
+      .icon(sized("c.png", 48))
+      .icon(sized("d.png", 48));
+    assert_eq!(select(&manifest, &IconQuery::new(96)), Some("c.png"));
+  }
+
+  #[test]
+  fn filters_by_purpose_and_type() {
+    let maskable = Purposes::from(Purpose::Maskable);
+    let manifest = Manifest::builder("App")
+      .icon(sized("96.webp", 96))
+      .icon(sized("mask.png", 96).purpose(maskable))
+      .icon(sized("192.png", 192));
+    let query = IconQuery::new(96).mime_type("image/png");
+    assert_eq!(select(&manifest, &query), Some("192.png"));
+    let query = IconQuery::new(96).purpose(Purpose::Maskable);
+    assert_eq!(select(&manifest, &query), Some("mask.png"));
+    let query = IconQuery::new(96).purpose(Purpose::Monochrome);
+    assert_eq!(select(&manifest, &query), None);
+  }
+}
@@ -0,0 +1,939 @@
+use serde_json::value::RawValue;
+use url::form_urlencoded;
+
+use super::cow;
+use super::percent;
+use super::process::{string, Processor};
+use super::{Diagnostic, Error};
+
+use std::borrow::Cow;
+
+/// The HTTP method a share target is invoked with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ShareMethod {
+  /// The shared data is sent in the query string. This is the default.
+  #[serde(rename = "GET", alias = "get")]
+  Get,
+  /// The shared data is sent in the request body.
+  #[serde(rename = "POST", alias = "post")]
+  Post,
+}
+
+impl ShareMethod {
+  /// Get the keyword for this method.
+  #[must_use]
+  #[inline]
+  pub fn as_str(self) -> &'static str {
+    match self {
+      ShareMethod::Get => "GET",
+      ShareMethod::Post => "POST",
+    }
+  }
+}
+
+/// The encoding of the shared data in a `POST` request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ShareEnctype {
+  /// `application/x-www-form-urlencoded`. This is the default.
+  #[serde(rename = "application/x-www-form-urlencoded")]
+  UrlEncoded,
+  /// `multipart/form-data`. Required to share files.
+  #[serde(rename = "multipart/form-data")]
+  Multipart,
+}
+
+impl ShareEnctype {
+  /// Get the MIME type for this encoding.
+  #[must_use]
+  #[inline]
+  pub fn as_str(self) -> &'static str {
+    match self {
+      ShareEnctype::UrlEncoded => "application/x-www-form-urlencoded",
+      ShareEnctype::Multipart => "multipart/form-data",
+    }
+  }
+}
+
+/// Register the application as a target of the system share dialog.
+/// [Read more.](https://w3c.github.io/web-share-target/)
+///
+/// ## Example Output
+/// ```json
+/// "share_target": {
+///   "action": "/share",
+///   "method": "POST",
+///   "enctype": "multipart/form-data",
+///   "params": {
+///     "title": "name",
+///     "text": "description",
+///     "url": "link",
+///     "files": [{
+///       "name": "photos",
+///       "accept": ["image/*", ".heic"]
+///     }]
+///   }
+/// }
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShareTarget<'a> {
+  #[serde(borrow)]
+  action: Cow<'a, str>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  method: Option<ShareMethod>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  enctype: Option<ShareEnctype>,
+  #[serde(borrow, default)]
+  params: ShareParams<'a>,
+}
+
+impl<'a> ShareTarget<'a> {
+  /// Create a new `ShareTarget` instance for the URL `action`.
+  ///
+  /// ## Example
+  /// ```rust
+  /// # extern crate webmanifest;
+  /// # use webmanifest::{ShareEnctype, ShareFile, ShareMethod, ShareTarget};
+  /// let share_target = ShareTarget::new("/share")
+  ///   .method(ShareMethod::Post)
+  ///   .enctype(ShareEnctype::Multipart)
+  ///   .title("name")
+  ///   .file(ShareFile::new("photos").accept("image/*"));
+  /// ```
+  #[must_use]
+  #[inline]
+  pub fn new(action: impl Into<Cow<'a, str>>) -> Self {
+    Self {
+      action: action.into(),
+      method: None,
+      enctype: None,
+      params: ShareParams::default(),
+    }
+  }
+
+  /// Set the `method` value.
+  #[must_use]
+  #[inline]
+  pub fn method(mut self, method: ShareMethod) -> Self {
+    self.method = Some(method);
+    self
+  }
+
+  /// Set the `enctype` value.
+  #[must_use]
+  #[inline]
+  pub fn enctype(mut self, enctype: ShareEnctype) -> Self {
+    self.enctype = Some(enctype);
+    self
+  }
+
+  /// Set the name of the field that receives the shared title.
+  #[must_use]
+  #[inline]
+  pub fn title(mut self, name: impl Into<Cow<'a, str>>) -> Self {
+    self.params.title = Some(name.into());
+    self
+  }
+
+  /// Set the name of the field that receives the shared text.
+  #[must_use]
+  #[inline]
+  pub fn text(mut self, name: impl Into<Cow<'a, str>>) -> Self {
+    self.params.text = Some(name.into());
+    self
+  }
+
+  /// Set the name of the field that receives the shared URL.
+  #[must_use]
+  #[inline]
+  pub fn url(mut self, name: impl Into<Cow<'a, str>>) -> Self {
+    self.params.url = Some(name.into());
+    self
+  }
+
+  /// Add a field that receives shared files.
+  #[must_use]
+  #[inline]
+  pub fn file(mut self, file: ShareFile<'a>) -> Self {
+    self.params.files.push(file);
+    self
+  }
+
+  /// Convert into a share target that owns all of its strings.
+  #[must_use]
+  pub fn into_owned(self) -> ShareTarget<'static> {
+    ShareTarget {
+      action: cow::owned(self.action),
+      method: self.method,
+      enctype: self.enctype,
+      params: self.params.into_owned(),
+    }
+  }
+
+  /// Get the `action` value.
+  #[must_use]
+  #[inline]
+  pub fn get_action(&self) -> &str {
+    &self.action
+  }
+
+  /// Get the `method` value. Defaults to `ShareMethod::Get` if not set.
+  #[must_use]
+  #[inline]
+  pub fn get_method(&self) -> ShareMethod {
+    self.method.unwrap_or(ShareMethod::Get)
+  }
+
+  /// Get the `enctype` value. Defaults to `ShareEnctype::UrlEncoded` if not
+  /// set.
+  #[must_use]
+  #[inline]
+  pub fn get_enctype(&self) -> ShareEnctype {
+    self.enctype.unwrap_or(ShareEnctype::UrlEncoded)
+  }
+
+  /// Get the `params` value.
+  #[must_use]
+  #[inline]
+  pub fn get_params(&self) -> &ShareParams<'a> {
+    &self.params
+  }
+
+  /// Parse the data of an incoming share request into the declared fields.
+  ///
+  /// `content_type` is the `Content-Type` header of the request. For `GET`
+  /// requests, pass `application/x-www-form-urlencoded` and the query string
+  /// of the request URL as `body`. Fields that were not declared in `params`
+  /// are ignored.
+  ///
+  /// ## Errors
+  /// Fails if `content_type` is not `application/x-www-form-urlencoded` or
+  /// `multipart/form-data`, or if the body is not valid for it.
+  ///
+  /// ## Example
+  /// ```rust
+  /// # extern crate webmanifest;
+  /// # use webmanifest::ShareTarget;
+  /// # fn main() -> Result<(), webmanifest::Error> {
+  /// let share_target = ShareTarget::new("/share").title("name").url("link");
+  /// let data = share_target.parse_request(
+  ///   "application/x-www-form-urlencoded",
+  ///   b"name=Hello%20world&link=https%3A%2F%2Fexample.com%2F&other=1",
+  /// )?;
+  /// assert_eq!(data.get_title(), Some("Hello world"));
+  /// assert_eq!(data.get_url(), Some("https://example.com/"));
+  /// assert_eq!(data.get_text(), None);
+  /// # Ok(())}
+  /// ```
+  pub fn parse_request(
+    &self,
+    content_type: &str,
+    body: &[u8],
+  ) -> Result<SharedData, Error> {
+    let mut parameters = content_type.split(';');
+    let essence = parameters.next().unwrap_or("").trim().to_ascii_lowercase();
+    let mut data = SharedData::default();
+
+    if essence == ShareEnctype::UrlEncoded.as_str() {
+      for (name, value) in form_urlencoded::parse(body) {
+        self.params.assign(&mut data, &name, value.into_owned());
+      }
+      return Ok(data);
+    }
+
+    if essence != ShareEnctype::Multipart.as_str() {
+      return Err(Error::InvalidRequest("unsupported content type"));
+    }
+    let boundary = parameters
+      .filter_map(|parameter| parameter.split_once('='))
+      .find(|(name, _)| name.trim().eq_ignore_ascii_case("boundary"))
+      .map(|(_, value)| unquote(value.trim()))
+      .ok_or(Error::InvalidRequest("missing multipart boundary"))?;
+
+    for part in multipart(body, boundary)? {
+      let is_file = self.params.files.iter().any(|file| file.name == part.name);
+      if is_file && part.filename.is_some() {
+        data.files.push(SharedFile {
+          name: part.name,
+          filename: part.filename,
+          content_type: part.content_type,
+          data: part.data.to_vec(),
+        });
+      } else {
+        let value = String::from_utf8_lossy(part.data).into_owned();
+        self.params.assign(&mut data, &part.name, value);
+      }
+    }
+    Ok(data)
+  }
+
+  /// Process the `share_target` member.
+  pub(crate) fn process(
+    raw: &'a RawValue,
+    path: &str,
+    diagnostics: &mut Vec<Diagnostic>,
+  ) -> Option<Self> {
+    let mut processor = Processor::object(raw, path, diagnostics)?;
+    let action = processor.required_text("action");
+    let method = processor.keyword("method");
+    let enctype = processor.keyword("enctype");
+    let params = processor.nested("params", ShareParams::process);
+    processor.finish(diagnostics);
+    Some(Self {
+      action: action?,
+      method,
+      enctype,
+      params: params.unwrap_or_default(),
+    })
+  }
+}
+
+/// The names of the fields a share target receives the shared data in.
+///
+/// Created with the `ShareTarget` builder methods.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ShareParams<'a> {
+  #[serde(skip_serializing_if = "Option::is_none")]
+  #[serde(borrow, default, deserialize_with = "cow::option")]
+  title: Option<Cow<'a, str>>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  #[serde(borrow, default, deserialize_with = "cow::option")]
+  text: Option<Cow<'a, str>>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  #[serde(borrow, default, deserialize_with = "cow::option")]
+  url: Option<Cow<'a, str>>,
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  #[serde(borrow, default, deserialize_with = "cow::one_or_many")]
+  files: Vec<ShareFile<'a>>,
+}
+
+impl<'a> ShareParams<'a> {
+  /// Get the name of the field that receives the shared title.
+  #[must_use]
+  #[inline]
+  pub fn get_title(&self) -> Option<&str> {
+    self.title.as_deref()
+  }
+
+  /// Get the name of the field that receives the shared text.
+  #[must_use]
+  #[inline]
+  pub fn get_text(&self) -> Option<&str> {
+    self.text.as_deref()
+  }
+
+  /// Get the name of the field that receives the shared URL.
+  #[must_use]
+  #[inline]
+  pub fn get_url(&self) -> Option<&str> {
+    self.url.as_deref()
+  }
+
+  /// Get the fields that receive shared files.
+  #[must_use]
+  #[inline]
+  pub fn get_files(&self) -> &[ShareFile<'a>] {
+    &self.files
+  }
+
+  /// Check if no field is declared.
+  #[must_use]
+  #[inline]
+  pub fn is_empty(&self) -> bool {
+    self.title.is_none()
+      && self.text.is_none()
+      && self.url.is_none()
+      && self.files.is_empty()
+  }
+
+  fn into_owned(self) -> ShareParams<'static> {
+    ShareParams {
+      title: cow::owned_option(self.title),
+      text: cow::owned_option(self.text),
+      url: cow::owned_option(self.url),
+      files: self.files.into_iter().map(ShareFile::into_owned).collect(),
+    }
+  }
+
+  /// Store `value` in the member of `data` that the field `name` maps to.
+  fn assign(&self, data: &mut SharedData, name: &str, value: String) {
+    if self.title.as_deref() == Some(name) {
+      data.title = Some(value);
+    } else if self.text.as_deref() == Some(name) {
+      data.text = Some(value);
+    } else if self.url.as_deref() == Some(name) {
+      data.url = Some(value);
+    }
+  }
+
+  /// Process the `params` member of a share target.
+  fn process(
+    raw: &'a RawValue,
+    path: &str,
+    diagnostics: &mut Vec<Diagnostic>,
+  ) -> Option<Self> {
+    let mut processor = Processor::object(raw, path, diagnostics)?;
+    let title = processor.text("title");
+    let text = processor.text("text");
+    let url = processor.text("url");
+    let files = processor.one_or_many("files", ShareFile::process);
+    processor.finish(diagnostics);
+    Some(Self {
+      title,
+      text,
+      url,
+      files,
+    })
+  }
+}
+
+/// A field of a share target that receives shared files.
+///
+/// ## Example
+/// ```rust
+/// # extern crate webmanifest;
+/// # use webmanifest::ShareFile;
+/// let file = ShareFile::new("photos").accept("image/*").accept(".heic");
+/// assert!(file.accepts(Some("image/png"), None));
+/// assert!(file.accepts(None, Some("IMG_0001.HEIC")));
+/// assert!(!file.accepts(Some("text/plain"), Some("notes.txt")));
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShareFile<'a> {
+  #[serde(borrow)]
+  name: Cow<'a, str>,
+  #[serde(default, deserialize_with = "cow::list")]
+  accept: Vec<Cow<'a, str>>,
+}
+
+impl<'a> ShareFile<'a> {
+  /// Create a new `ShareFile` instance for the field `name`.
+  #[must_use]
+  #[inline]
+  pub fn new(name: impl Into<Cow<'a, str>>) -> Self {
+    Self {
+      name: name.into(),
+      accept: vec![],
+    }
+  }
+
+  /// Add an accepted MIME type such as `image/png` or `image/*`, or a file
+  /// extension such as `.png`.
+  #[must_use]
+  #[inline]
+  pub fn accept(mut self, accept: impl Into<Cow<'a, str>>) -> Self {
+    self.accept.push(accept.into());
+    self
+  }
+
+  /// Convert into a file field that owns all of its strings.
+  #[must_use]
+  pub fn into_owned(self) -> ShareFile<'static> {
+    ShareFile {
+      name: cow::owned(self.name),
+      accept: self.accept.into_iter().map(cow::owned).collect(),
+    }
+  }
+
+  /// Get the `name` value.
+  #[must_use]
+  #[inline]
+  pub fn get_name(&self) -> &str {
+    &self.name
+  }
+
+  /// Get the `accept` value.
+  #[must_use]
+  #[inline]
+  pub fn get_accept(&self) -> &[Cow<'a, str>] {
+    &self.accept
+  }
+
+  /// Check if a file with the given MIME type or file name is accepted.
+  /// MIME types and extensions are matched case-insensitively.
+  #[must_use]
+  pub fn accepts(
+    &self,
+    mime_type: Option<&str>,
+    filename: Option<&str>,
+  ) -> bool {
+    self.accept.iter().any(|accept| {
+      let accept = accept.trim();
+      if accept.starts_with('.') {
+        return filename.is_some_and(|filename| {
+          let filename = filename.to_ascii_lowercase();
+          filename.ends_with(&accept.to_ascii_lowercase())
+        });
+      }
+      mime_type.is_some_and(|mime_type| match accept.strip_suffix("/*") {
+        Some(kind) => mime_type
+          .split('/')
+          .next()
+          .is_some_and(|prefix| prefix.eq_ignore_ascii_case(kind)),
+        None => mime_type.eq_ignore_ascii_case(accept),
+      })
+    })
+  }
+
+  /// Process an entry of the `files` member of a share target.
+  fn process(
+    raw: &'a RawValue,
+    path: &str,
+    diagnostics: &mut Vec<Diagnostic>,
+  ) -> Option<Self> {
+    let mut processor = Processor::object(raw, path, diagnostics)?;
+    let name = processor.required_text("name");
+    let accept = processor.one_or_many("accept", string);
+    processor.finish(diagnostics);
+    Some(Self {
+      name: name?,
+      accept,
+    })
+  }
+}
+
+/// The data received by a share target. Created by
+/// `ShareTarget::parse_request`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SharedData {
+  title: Option<String>,
+  text: Option<String>,
+  url: Option<String>,
+  files: Vec<SharedFile>,
+}
+
+impl SharedData {
+  /// Get the shared title.
+  #[must_use]
+  #[inline]
+  pub fn get_title(&self) -> Option<&str> {
+    self.title.as_deref()
+  }
+
+  /// Get the shared text.
+  #[must_use]
+  #[inline]
+  pub fn get_text(&self) -> Option<&str> {
+    self.text.as_deref()
+  }
+
+  /// Get the shared URL.
+  #[must_use]
+  #[inline]
+  pub fn get_url(&self) -> Option<&str> {
+    self.url.as_deref()
+  }
+
+  /// Get the shared files.
+  #[must_use]
+  #[inline]
+  pub fn get_files(&self) -> &[SharedFile] {
+    &self.files
+  }
+}
+
+/// A file received by a share target.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SharedFile {
+  name: String,
+  filename: Option<String>,
+  content_type: Option<String>,
+  data: Vec<u8>,
+}
+
+impl SharedFile {
+  /// Get the name of the field the file was sent in.
+  #[must_use]
+  #[inline]
+  pub fn get_name(&self) -> &str {
+    &self.name
+  }
+
+  /// Get the file name sent by the client.
+  #[must_use]
+  #[inline]
+  pub fn get_filename(&self) -> Option<&str> {
+    self.filename.as_deref()
+  }
+
+  /// Get the MIME type sent by the client.
+  #[must_use]
+  #[inline]
+  pub fn get_content_type(&self) -> Option<&str> {
+    self.content_type.as_deref()
+  }
+
+  /// Get the contents of the file.
+  #[must_use]
+  #[inline]
+  pub fn get_data(&self) -> &[u8] {
+    &self.data
+  }
+}
+
+/// A part of a `multipart/form-data` body.
+struct Part<'b> {
+  name: String,
+  filename: Option<String>,
+  content_type: Option<String>,
+  data: &'b [u8],
+}
+
+/// Split a `multipart/form-data` body into its parts.
+///
+/// Lines may end in CRLF, as required, or in a bare LF. The preamble before
+/// the first delimiter and the epilogue after the last one are ignored, and
+/// so are parts without a name.
+fn multipart<'b>(
+  body: &'b [u8],
+  boundary: &str,
+) -> Result<Vec<Part<'b>>, Error> {
+  let delimiter = format!("--{}", boundary);
+  let delimiter = delimiter.as_bytes();
+  let (_, mut index) =
+    find_delimiter(body, delimiter, 0).ok_or_else(malformed)?;
+
+  let mut parts = vec![];
+  loop {
+    if body[index..].starts_with(b"--") {
+      return Ok(parts);
+    }
+    // Skip the transport padding after the delimiter.
+    while let Some(b' ') | Some(b'\t') = body.get(index) {
+      index += 1;
+    }
+    let mut headers = vec![];
+    let (line, next) = read_line(body, index).ok_or_else(malformed)?;
+    if !line.is_empty() {
+      return Err(malformed());
+    }
+    index = next;
+    loop {
+      let (line, next) = read_line(body, index).ok_or_else(malformed)?;
+      if line.is_empty() {
+        break;
+      }
+      headers.push(String::from_utf8_lossy(line));
+      index = next;
+    }
+    let data_start = read_line(body, index).ok_or_else(malformed)?.1;
+    let (data_end, next) =
+      find_delimiter(body, delimiter, data_start).ok_or_else(malformed)?;
+
+    let mut name = None;
+    let mut filename = None;
+    let mut content_type = None;
+    for line in &headers {
+      let (header, value) = match line.split_once(':') {
+        Some(header) => header,
+        None => continue,
+      };
+      if header.trim().eq_ignore_ascii_case("content-disposition") {
+        let mut extended_filename = None;
+        for (key, value) in parameters(value) {
+          match key.as_str() {
+            "name" => name = Some(value),
+            "filename" => filename = Some(value),
+            "filename*" => extended_filename = decode_extended(&value),
+            _ => {}
+          }
+        }
+        filename = extended_filename.or(filename);
+      } else if header.trim().eq_ignore_ascii_case("content-type") {
+        content_type = Some(value.trim().to_string());
+      }
+    }
+
+    if let Some(name) = name {
+      parts.push(Part {
+        name,
+        filename,
+        content_type,
+        data: &body[data_start..data_end],
+      });
+    }
+    index = next;
+  }
+}
+
+/// Find the next delimiter line at or after `from`, which must be the start
+/// of a line. Returns where the data before it ends, excluding the line break
+/// that belongs to the delimiter, and where the delimiter ends.
+fn find_delimiter(
+  body: &[u8],
+  delimiter: &[u8],
+  from: usize,
+) -> Option<(usize, usize)> {
+  let mut at = from;
+  loop {
+    let start = find(body, delimiter, at)?;
+    let end = start + delimiter.len();
+    let line_start = start == from || body[start - 1] == b'\n';
+    let line_end = match body.get(end) {
+      Some(b'-') => body.get(end + 1) == Some(&b'-'),
+      Some(b'\r') | Some(b'\n') | Some(b' ') | Some(b'\t') => true,
+      _ => false,
+    };
+    if line_start && line_end {
+      let mut data_end = start;
+      if data_end > from && body[data_end - 1] == b'\n' {
+        data_end -= 1;
+        if data_end > from && body[data_end - 1] == b'\r' {
+          data_end -= 1;
+        }
+      }
+      return Some((data_end, end));
+    }
+    at = start + 1;
+  }
+}
+
+/// Read the line starting at `from`, without its CRLF or LF ending. Returns
+/// the line and the start of the next one, or `None` if the line doesn't end.
+fn read_line(body: &[u8], from: usize) -> Option<(&[u8], usize)> {
+  let end = from + body.get(from..)?.iter().position(|b| *b == b'\n')?;
+  let line = &body[from..end];
+  let line = line.strip_suffix(b"\r").unwrap_or(line);
+  Some((line, end + 1))
+}
+
+/// Parse the `key=value` parameters that follow the first `;` of a header
+/// value. Keys are lowercased, and quoted values are unquoted and unescaped.
+fn parameters(value: &str) -> Vec<(String, String)> {
+  let mut parameters = vec![];
+  let mut chars = value.chars().skip_while(|c| *c != ';').peekable();
+  while chars.next().is_some() {
+    let key: String = chars.by_ref().take_while(|c| *c != '=').collect();
+    let key = key.trim().to_ascii_lowercase();
+    while let Some(' ') | Some('\t') = chars.peek() {
+      chars.next();
+    }
+    let mut value = String::new();
+    if chars.peek() == Some(&'"') {
+      chars.next();
+      while let Some(c) = chars.next() {
+        match c {
+          '"' => break,
+          '\\' => value.extend(chars.next()),
+          c => value.push(c),
+        }
+      }
+      while chars.peek().is_some_and(|c| *c != ';') {
+        chars.next();
+      }
+    } else {
+      while let Some(c) = chars.peek().filter(|c| **c != ';') {
+        value.push(*c);
+        chars.next();
+      }
+      value = value.trim().to_string();
+    }
+    if !key.is_empty() {
+      parameters.push((key, value));
+    }
+  }
+  parameters
+}
+
+/// Decode an RFC 8187 extended parameter value such as
+/// `UTF-8''na%C3%AFve.txt`. Only UTF-8 and ISO-8859-1 are supported.
+fn decode_extended(value: &str) -> Option<String> {
+  let mut fields = value.splitn(3, '\'');
+  let charset = fields.next()?.trim();
+  let _language = fields.next()?;
+  let bytes = percent::decode(fields.next()?);
+  if charset.eq_ignore_ascii_case("utf-8") {
+    String::from_utf8(bytes).ok()
+  } else if charset.eq_ignore_ascii_case("iso-8859-1") {
+    Some(bytes.into_iter().map(char::from).collect())
+  } else {
+    None
+  }
+}
+
+/// The error for a `multipart/form-data` body that can't be split into parts.
+fn malformed() -> Error {
+  Error::InvalidRequest("malformed multipart body")
+}
+
+/// Find the first occurrence of `needle` in `haystack` at or after `from`.
+fn find(haystack: &[u8], needle: &[u8], from: usize) -> Option<usize> {
+  haystack
+    .get(from..)?
+    .windows(needle.len())
+    .position(|window| window == needle)
+    .map(|position| from + position)
+}
+
+/// Remove the quotes around a header parameter value.
+fn unquote(value: &str) -> &str {
+  value
+    .strip_prefix('"')
+    .and_then(|value| value.strip_suffix('"'))
+    .unwrap_or(value)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const CONTENT_TYPE: &str = "multipart/form-data; boundary=abc";
+
+  fn share_target() -> ShareTarget<'static> {
+    ShareTarget::new("/share")
+      .title("title")
+      .text("text")
+      .file(ShareFile::new("photos"))
+  }
+
+  fn parse(content_type: &str, body: &str) -> Result<SharedData, Error> {
+    share_target().parse_request(content_type, body.as_bytes())
+  }
+
+  #[test]
+  fn parses_crlf_body() {
+    let body = b"--abc\r\n\
+      Content-Disposition: form-data; name=\"title\"\r\n\
+      \r\n\
+      Hello\r\n\
+      --abc\r\n\
+      Content-Disposition: form-data; name=\"photos\"; filename=\"a.png\"\r\n\
+      Content-Type: image/png\r\n\
+      \r\n\
+      \x89PNG\r\n\r\n\
+      --abc--\r\n";
+    let data = share_target().parse_request(CONTENT_TYPE, body).unwrap();
+    assert_eq!(data.get_title(), Some("Hello"));
+    let file = &data.get_files()[0];
+    assert_eq!(file.get_name(), "photos");
+    assert_eq!(file.get_filename(), Some("a.png"));
+    assert_eq!(file.get_content_type(), Some("image/png"));
+    assert_eq!(file.get_data(), b"\x89PNG\r\n");
+  }
+
+  #[test]
+  fn parses_lf_body() {
+    let body = "--abc\n\
+      Content-Disposition: form-data; name=\"title\"\n\
+      \n\
+      Hello\n\
+      --abc\n\
+      Content-Disposition: form-data; name=\"text\"\n\
+      \n\
+      two\nlines\n\
+      --abc--\n";
+    let data = parse(CONTENT_TYPE, body).unwrap();
+    assert_eq!(data.get_title(), Some("Hello"));
+    assert_eq!(data.get_text(), Some("two\nlines"));
+  }
+
+  #[test]
+  fn parses_quoted_boundary() {
+    let content_type = "multipart/form-data; charset=utf-8; boundary=\"a:b c\"";
+    let body = "--a:b c\r\n\
+      Content-Disposition: form-data; name=title\r\n\
+      \r\n\
+      Hello\r\n\
+      --a:b c--";
+    let data = parse(content_type, body).unwrap();
+    assert_eq!(data.get_title(), Some("Hello"));
+  }
+
+  #[test]
+  fn prefers_extended_filename() {
+    let body = "--abc\r\n\
+      Content-Disposition: form-data; name=\"photos\"; \
+      filename=\"naive.txt\"; filename*=UTF-8''na%C3%AFve.txt\r\n\
+      \r\n\
+      data\r\n\
+      --abc--";
+    let data = parse(CONTENT_TYPE, body).unwrap();
+    assert_eq!(data.get_files()[0].get_filename(), Some("na\u{ef}ve.txt"));
+  }
+
+  #[test]
+  fn parses_quoted_parameters() {
+    let body = "--abc\r\n\
+      Content-Disposition: form-data; name=\"photos\"; \
+      filename=\"a;b \\\"c\\\".txt\"\r\n\
+      \r\n\
+      data\r\n\
+      --abc--";
+    let data = parse(CONTENT_TYPE, body).unwrap();
+    assert_eq!(data.get_files()[0].get_filename(), Some("a;b \"c\".txt"));
+  }
+
+  #[test]
+  fn ignores_part_without_headers() {
+    let body = "--abc\r\n\
+      \r\n\
+      orphan\r\n\
+      --abc\r\n\
+      Content-Disposition: form-data; name=\"title\"\r\n\
+      \r\n\
+      Hello\r\n\
+      --abc--";
+    let data = parse(CONTENT_TYPE, body).unwrap();
+    assert_eq!(data.get_title(), Some("Hello"));
+    assert_eq!(data.get_text(), None);
+  }
+
+  #[test]
+  fn ignores_preamble_and_epilogue() {
+    let body = "This is the preamble, not --abc\r\n\
+      --abc\r\n\
+      Content-Disposition: form-data; name=\"title\"\r\n\
+      \r\n\
+      Hello\r\n\
+      --abc--\r\n\
+      This is the epilogue.\r\n\
+      --abc\r\n";
+    let data = parse(CONTENT_TYPE, body).unwrap();
+    assert_eq!(data.get_title(), Some("Hello"));
+  }
+
+  #[test]
+  fn parses_empty_part() {
+    let body = "--abc\r\n\
+      Content-Disposition: form-data; name=\"title\"\r\n\
+      \r\n\
+      \r\n\
+      --abc--";
+    let data = parse(CONTENT_TYPE, body).unwrap();
+    assert_eq!(data.get_title(), Some(""));
+  }
+
+  #[test]
+  fn keeps_boundary_string_inside_body() {
+    let body = "--abc\r\n\
+      Content-Disposition: form-data; name=\"text\"\r\n\
+      \r\n\
+      a--abc\r\n\
+      --abcdef\r\n\
+      --abc--\r\n\
+      Content-Disposition: form-data; name=\"title\"\r\n\
+      \r\n\
+      Hello\r\n\
+      --abc--";
+    let data = parse(CONTENT_TYPE, body).unwrap();
+    assert_eq!(data.get_text(), Some("a--abc\r\n--abcdef"));
+    assert_eq!(data.get_title(), None);
+  }
+
+  #[test]
+  fn rejects_missing_closing_delimiter() {
+    let body = "--abc\r\n\
+      Content-Disposition: form-data; name=\"title\"\r\n\
+      \r\n\
+      Hello\r\n";
+    assert!(parse(CONTENT_TYPE, body).is_err());
+    assert!(parse(CONTENT_TYPE, "--abc\r\n\r\nHello\r\n--abc").is_err());
+    assert!(parse(CONTENT_TYPE, "no delimiter").is_err());
+    assert!(parse(CONTENT_TYPE, "--abc").is_err());
+  }
+
+  #[test]
+  fn rejects_unsupported_content_type() {
+    assert!(parse("text/plain", "").is_err());
+    assert!(parse("multipart/form-data", "").is_err());
+  }
+}
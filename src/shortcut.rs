@@ -0,0 +1,170 @@
+use serde_json::value::RawValue;
+
+use super::cow;
+use super::process::Processor;
+use super::{Diagnostic, Icon};
+
+use std::borrow::Cow;
+
+/// Add a shortcut to a key task in the application. Launchers show shortcuts
+/// in a jump list, e.g. when the application icon is long-pressed.
+///
+/// ## Example Output
+/// ```json
+/// "shortcuts": [{
+///   "name": "Open Play Later",
+///   "short_name": "Play Later",
+///   "description": "View the list of podcasts you saved for later",
+///   "url": "/play-later",
+///   "icons": [{
+///     "src": "/icons/play-later.png",
+///     "sizes": "96x96",
+///     "type": "image/png"
+///   }]
+/// }]
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Shortcut<'a> {
+  #[serde(borrow)]
+  name: Cow<'a, str>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  #[serde(borrow, default, deserialize_with = "cow::option")]
+  short_name: Option<Cow<'a, str>>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  #[serde(borrow, default, deserialize_with = "cow::option")]
+  description: Option<Cow<'a, str>>,
+  #[serde(borrow)]
+  url: Cow<'a, str>,
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  #[serde(borrow, default)]
+  icons: Vec<Icon<'a>>,
+}
+
+impl<'a> Shortcut<'a> {
+  /// Create a new `Shortcut` instance.
+  ///
+  /// ## Example
+  /// ```rust
+  /// # extern crate webmanifest;
+  /// # use webmanifest::{Icon, Shortcut};
+  /// let shortcut = Shortcut::new("Open Play Later", "/play-later")
+  ///   .short_name("Play Later")
+  ///   .icon(Icon::new("/icons/play-later.png", (96, 96)));
+  /// ```
+  #[must_use]
+  #[inline]
+  pub fn new(
+    name: impl Into<Cow<'a, str>>,
+    url: impl Into<Cow<'a, str>>,
+  ) -> Self {
+    Self {
+      name: name.into(),
+      short_name: None,
+      description: None,
+      url: url.into(),
+      icons: vec![],
+    }
+  }
+
+  /// Set the `short_name` value, used where there is not enough space for
+  /// `name`.
+  #[must_use]
+  #[inline]
+  pub fn short_name(mut self, short_name: impl Into<Cow<'a, str>>) -> Self {
+    self.short_name = Some(short_name.into());
+    self
+  }
+
+  /// Set the `description` value.
+  #[must_use]
+  #[inline]
+  pub fn description(mut self, description: impl Into<Cow<'a, str>>) -> Self {
+    self.description = Some(description.into());
+    self
+  }
+
+  /// Add an `Icon` to the icons vector. Accepts an `Icon` or a reference to
+  /// one.
+  #[must_use]
+  #[inline]
+  pub fn icon(mut self, icon: impl Into<Icon<'a>>) -> Self {
+    self.icons.push(icon.into());
+    self
+  }
+
+  /// Convert into a shortcut that owns all of its strings.
+  #[must_use]
+  pub fn into_owned(self) -> Shortcut<'static> {
+    Shortcut {
+      name: cow::owned(self.name),
+      short_name: cow::owned_option(self.short_name),
+      description: cow::owned_option(self.description),
+      url: cow::owned(self.url),
+      icons: self.icons.into_iter().map(Icon::into_owned).collect(),
+    }
+  }
+
+  /// Get the `name` value.
+  #[must_use]
+  #[inline]
+  pub fn get_name(&self) -> &str {
+    &self.name
+  }
+
+  /// Get the `short_name` value.
+  #[must_use]
+  #[inline]
+  pub fn get_short_name(&self) -> Option<&str> {
+    self.short_name.as_deref()
+  }
+
+  /// Get the `description` value.
+  #[must_use]
+  #[inline]
+  pub fn get_description(&self) -> Option<&str> {
+    self.description.as_deref()
+  }
+
+  /// Get the `url` value.
+  #[must_use]
+  #[inline]
+  pub fn get_url(&self) -> &str {
+    &self.url
+  }
+
+  /// Get the `icons` value.
+  #[must_use]
+  #[inline]
+  pub fn get_icons(&self) -> &[Icon<'a>] {
+    &self.icons
+  }
+
+  /// Process an entry of the `shortcuts` member.
+  pub(crate) fn process(
+    raw: &'a RawValue,
+    path: &str,
+    diagnostics: &mut Vec<Diagnostic>,
+  ) -> Option<Self> {
+    let mut processor = Processor::object(raw, path, diagnostics)?;
+    let name = processor.required_text("name");
+    let short_name = processor.text("short_name");
+    let description = processor.text("description");
+    let url = processor.required_text("url");
+    let icons = processor.list("icons", Icon::process);
+    processor.finish(diagnostics);
+    Some(Self {
+      name: name?,
+      short_name,
+      description,
+      url: url?,
+      icons,
+    })
+  }
+}
+
+impl<'a, 'b> From<&'b Shortcut<'a>> for Shortcut<'a> {
+  #[inline]
+  fn from(shortcut: &'b Shortcut<'a>) -> Self {
+    shortcut.clone()
+  }
+}
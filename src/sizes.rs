@@ -0,0 +1,297 @@
+use serde::de::Error as DeError;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use std::fmt;
+use std::iter::FromIterator;
+use std::slice;
+use std::str::FromStr;
+
+use super::cow::CowStr;
+use super::{Diagnostic, DiagnosticKind, Error};
+
+/// A single entry of an icon's `sizes` member.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Size {
+  /// The icon can be scaled to any size, e.g. an SVG.
+  Any,
+  /// The icon has fixed pixel dimensions.
+  Exact {
+    /// The width in pixels.
+    width: u32,
+    /// The height in pixels.
+    height: u32,
+  },
+}
+
+impl Size {
+  /// Parse a single `WxH` or `any` token. The `x` and `any` are matched
+  /// case-insensitively, and dimensions may not start with `0`.
+  ///
+  /// ## Example
+  /// ```rust
+  /// # extern crate webmanifest;
+  /// # use webmanifest::Size;
+  /// assert_eq!(Size::parse("48X48"), Some(Size::Exact { width: 48, height: 48 }));
+  /// assert_eq!(Size::parse("48*48"), None);
+  /// ```
+  #[must_use]
+  pub fn parse(token: &str) -> Option<Self> {
+    if token.eq_ignore_ascii_case("any") {
+      return Some(Size::Any);
+    }
+    let separator = token.find(['x', 'X'])?;
+    let width = dimension(&token[..separator])?;
+    let height = dimension(&token[separator + 1..])?;
+    Some(Size::Exact { width, height })
+  }
+}
+
+impl From<(u32, u32)> for Size {
+  #[inline]
+  fn from((width, height): (u32, u32)) -> Self {
+    Size::Exact { width, height }
+  }
+}
+
+impl fmt::Display for Size {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Size::Any => f.write_str("any"),
+      Size::Exact { width, height } => write!(f, "{}x{}", width, height),
+    }
+  }
+}
+
+/// Parse a dimension: ASCII digits, not starting with `0`.
+fn dimension(value: &str) -> Option<u32> {
+  let valid = !value.starts_with('0')
+    && !value.is_empty()
+    && value.bytes().all(|b| b.is_ascii_digit());
+  if valid {
+    value.parse().ok()
+  } else {
+    None
+  }
+}
+
+/// The `sizes` member of an icon: a space-separated list of `WxH` dimensions
+/// or the `any` keyword.
+///
+/// ## Example
+/// ```rust
+/// # extern crate webmanifest;
+/// # use webmanifest::{Size, Sizes};
+/// # fn main() -> Result<(), webmanifest::Error> {
+/// let sizes: Sizes = "16x16  32X32 any".parse()?;
+/// assert_eq!(sizes.to_string(), "16x16 32x32 any");
+/// assert!(sizes.contains(Size::Exact { width: 32, height: 32 }));
+/// assert!("48*48".parse::<Sizes>().is_err());
+/// # Ok(())}
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Sizes {
+  sizes: Vec<Size>,
+}
+
+impl Sizes {
+  /// Create an empty list.
+  #[must_use]
+  #[inline]
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Parse a `sizes` value, failing on the first invalid token or if there
+  /// are no sizes at all.
+  pub fn parse(value: &str) -> Result<Self, Error> {
+    let (sizes, invalid) = Self::parse_lenient(value);
+    let invalid = match invalid.into_iter().next() {
+      Some(token) => token,
+      None if sizes.is_empty() => value,
+      None => return Ok(sizes),
+    };
+    let kind = DiagnosticKind::InvalidValue {
+      value: invalid.to_string(),
+    };
+    Err(Error::InvalidValue(Diagnostic::error("sizes", kind)))
+  }
+
+  /// Parse a `sizes` value, skipping invalid tokens and returning them
+  /// alongside the valid sizes.
+  pub(crate) fn parse_lenient(value: &str) -> (Self, Vec<&str>) {
+    let mut sizes = Self::new();
+    let mut invalid = vec![];
+    for token in value.split_whitespace() {
+      match Size::parse(token) {
+        Some(size) => sizes = sizes.with(size),
+        None => invalid.push(token),
+      }
+    }
+    (sizes, invalid)
+  }
+
+  /// Add a size to the list.
+  #[must_use]
+  #[inline]
+  pub fn with(mut self, size: impl Into<Size>) -> Self {
+    let size = size.into();
+    if !self.sizes.contains(&size) {
+      self.sizes.push(size);
+    }
+    self
+  }
+
+  /// Check if the list contains a size.
+  #[must_use]
+  #[inline]
+  pub fn contains(&self, size: impl Into<Size>) -> bool {
+    self.sizes.contains(&size.into())
+  }
+
+  /// Check if the list contains the `any` keyword.
+  #[must_use]
+  #[inline]
+  pub fn is_any(&self) -> bool {
+    self.contains(Size::Any)
+  }
+
+  /// Check if the list is empty.
+  #[must_use]
+  #[inline]
+  pub fn is_empty(&self) -> bool {
+    self.sizes.is_empty()
+  }
+
+  /// Get the sizes as a slice.
+  #[must_use]
+  #[inline]
+  pub fn as_slice(&self) -> &[Size] {
+    &self.sizes
+  }
+
+  /// Iterate over the sizes.
+  #[inline]
+  pub fn iter(&self) -> slice::Iter<'_, Size> {
+    self.sizes.iter()
+  }
+}
+
+impl FromStr for Sizes {
+  type Err = Error;
+
+  #[inline]
+  fn from_str(value: &str) -> Result<Self, Self::Err> {
+    Self::parse(value)
+  }
+}
+
+impl From<Size> for Sizes {
+  #[inline]
+  fn from(size: Size) -> Self {
+    Self::new().with(size)
+  }
+}
+
+impl From<(u32, u32)> for Sizes {
+  #[inline]
+  fn from(size: (u32, u32)) -> Self {
+    Self::new().with(size)
+  }
+}
+
+impl<S: Into<Size>> FromIterator<S> for Sizes {
+  fn from_iter<I: IntoIterator<Item = S>>(iter: I) -> Self {
+    iter.into_iter().fold(Self::new(), Sizes::with)
+  }
+}
+
+impl<'s> IntoIterator for &'s Sizes {
+  type Item = &'s Size;
+  type IntoIter = slice::Iter<'s, Size>;
+
+  #[inline]
+  fn into_iter(self) -> Self::IntoIter {
+    self.iter()
+  }
+}
+
+impl fmt::Display for Sizes {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    for (index, size) in self.sizes.iter().enumerate() {
+      if index > 0 {
+        f.write_str(" ")?;
+      }
+      write!(f, "{}", size)?;
+    }
+    Ok(())
+  }
+}
+
+impl Serialize for Sizes {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(self)
+  }
+}
+
+impl<'de> Deserialize<'de> for Sizes {
+  fn deserialize<D: Deserializer<'de>>(
+    deserializer: D,
+  ) -> Result<Self, D::Error> {
+    let value = CowStr::deserialize(deserializer)?;
+    Self::parse(&value.0).map_err(D::Error::custom)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::super::{Icon, Manifest};
+  use super::*;
+
+  fn exact(width: u32, height: u32) -> Size {
+    Size::Exact { width, height }
+  }
+
+  #[test]
+  fn size_grammar() {
+    assert_eq!(Size::parse("48x48"), Some(exact(48, 48)));
+    assert_eq!(Size::parse("48X96"), Some(exact(48, 96)));
+    assert_eq!(Size::parse("ANY"), Some(Size::Any));
+    for token in &["48x48x48", "048x48", "48x048", "0x0", "x48", "48x", "48"] {
+      assert_eq!(Size::parse(token), None, "{}", token);
+    }
+    for token in &["+48x48", "48 x48", "4.8x48", "\u{664}8x48", "anyx"] {
+      assert_eq!(Size::parse(token), None, "{}", token);
+    }
+  }
+
+  #[test]
+  fn duplicate_sizes_are_kept_once() {
+    let sizes = Sizes::parse("48x48 any 48X48 ANY 96x96").unwrap();
+    assert_eq!(sizes.as_slice(), [exact(48, 48), Size::Any, exact(96, 96)]);
+    assert_eq!(sizes.to_string(), "48x48 any 96x96");
+  }
+
+  #[test]
+  fn empty_sizes_are_rejected() {
+    for value in &["", "  ", "\t"] {
+      assert!(Sizes::parse(value).is_err(), "{:?}", value);
+    }
+    assert!(Sizes::parse("48x48 48x48x48").is_err());
+  }
+
+  #[test]
+  fn empty_sizes_are_not_serialized() {
+    let json = r#"{
+      "name": "App",
+      "icons": [{ "src": "a.png", "sizes": "" }]
+    }"#;
+    assert!(Manifest::parse(json).is_err());
+    let (manifest, diagnostics) = Manifest::process(json).unwrap();
+    assert_eq!(manifest.get_icons()[0].get_sizes(), None);
+    assert_eq!(diagnostics[0].get_member(), "icons[0].sizes");
+    assert!(!manifest.build().unwrap().contains("sizes"));
+
+    let icon = Icon::new("a.png", Sizes::new());
+    assert_eq!(icon.get_sizes(), None);
+  }
+}
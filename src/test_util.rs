@@ -0,0 +1,24 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// The number of directories created by `temp_dir` so far.
+static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+/// Create an empty directory for a test. `name` only makes the directory
+/// easier to find: every call gets a new one, so tests that run in parallel
+/// never share a directory.
+pub(crate) fn temp_dir(name: &str) -> PathBuf {
+  let count = COUNT.fetch_add(1, Ordering::SeqCst);
+  let dir = env::temp_dir().join(format!(
+    "webmanifest-{}-{}-{}",
+    process::id(),
+    count,
+    name
+  ));
+  let _ = fs::remove_dir_all(&dir);
+  fs::create_dir_all(&dir).unwrap();
+  dir
+}
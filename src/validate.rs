@@ -0,0 +1,703 @@
+use unicode_segmentation::UnicodeSegmentation;
+use url::Url;
+
+use super::resolve::{default_scope, within_scope};
+use super::{
+  Color, Diagnostic, DiagnosticKind, Direction, Error, FormFactor, LanguageTag,
+  Manifest, Severity, ShareEnctype, ShareMethod, Size,
+};
+
+/// The smallest screenshot dimension shown in a richer install dialog.
+const SCREENSHOT_MIN: u32 = 320;
+/// The largest screenshot dimension shown in a richer install dialog.
+const SCREENSHOT_MAX: u32 = 3840;
+/// The largest ratio of the longest to the shortest side of a screenshot
+/// shown in a richer install dialog.
+const SCREENSHOT_RATIO: f64 = 2.3;
+/// The icon size launchers use for shortcuts.
+const SHORTCUT_ICON: (u32, u32) = (96, 96);
+
+/// Configurable manifest validation.
+///
+/// `Manifest::validate` and `Manifest::check` use the default configuration.
+///
+/// ## Example
+/// ```rust
+/// # extern crate webmanifest;
+/// # use webmanifest::{DiagnosticKind, Manifest, Url, Validator};
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let base_url = Url::parse("https://example.com/manifest.json")?;
+/// let manifest = Manifest::builder("My Cool Application")
+///   .short_name("My Cool App");
+/// let diagnostics = Validator::new()
+///   .short_name_max(10)
+///   .validate(&manifest, &base_url);
+/// assert_eq!(
+///   diagnostics[0].get_kind(),
+///   &DiagnosticKind::TooLong { length: 11, max: 10 }
+/// );
+/// # Ok(())}
+/// ```
+#[derive(Debug, Clone)]
+pub struct Validator {
+  short_name_max: usize,
+  shortcuts_max: usize,
+}
+
+impl Validator {
+  /// Create a new instance with the default configuration.
+  #[must_use]
+  #[inline]
+  pub fn new() -> Self {
+    Self {
+      short_name_max: 12,
+      shortcuts_max: 4,
+    }
+  }
+
+  /// Set the number of user-perceived characters after which `short_name`
+  /// is reported as too long. Defaults to 12, the length most launchers
+  /// display without truncating.
+  #[must_use]
+  #[inline]
+  pub fn short_name_max(mut self, max: usize) -> Self {
+    self.short_name_max = max;
+    self
+  }
+
+  /// Set the number of `shortcuts` after which the rest are reported as not
+  /// shown. Defaults to 4, the number Chrome shows on Android.
+  #[must_use]
+  #[inline]
+  pub fn shortcuts_max(mut self, max: usize) -> Self {
+    self.shortcuts_max = max;
+    self
+  }
+
+  /// Validate the manifest and fail if any diagnostic is an error.
+  ///
+  /// Returns the warnings if there are no errors.
+  pub fn check(
+    &self,
+    manifest: &Manifest,
+    base_url: &Url,
+  ) -> Result<Vec<Diagnostic>, Error> {
+    let diagnostics = self.validate(manifest, base_url);
+    if diagnostics
+      .iter()
+      .any(|d| d.get_severity() == Severity::Error)
+    {
+      Err(Error::Validation(diagnostics))
+    } else {
+      Ok(diagnostics)
+    }
+  }
+
+  /// Check the manifest for mistakes that make a user agent ignore or
+  /// truncate members.
+  ///
+  /// URL members are resolved against `base_url`, which is both the URL of
+  /// the manifest and the document that links it. Mistakes that make a user
+  /// agent ignore a member are reported as errors, and mistakes that only
+  /// limit how the application is shown, such as a `short_name` longer than
+  /// `short_name_max` graphemes or more than `shortcuts_max` shortcuts, as
+  /// warnings.
+  pub fn validate(
+    &self,
+    manifest: &Manifest,
+    base_url: &Url,
+  ) -> Vec<Diagnostic> {
+    let mut diagnostics = vec![];
+
+    if let Some(short_name) = manifest.get_short_name() {
+      let length = short_name.graphemes(true).count();
+      if length > self.short_name_max {
+        let kind = DiagnosticKind::TooLong {
+          length,
+          max: self.short_name_max,
+        };
+        diagnostics.push(Diagnostic::warning("short_name", kind));
+      }
+    }
+
+    let colors = [
+      ("background_color", manifest.get_bg_color()),
+      ("theme_color", manifest.get_theme_color()),
+    ];
+    for (member, color) in colors.iter() {
+      if let Some(color) = color {
+        if let Err(kind) = Color::parse_kind(color) {
+          diagnostics.push(Diagnostic::error(*member, kind));
+        }
+      }
+    }
+
+    validate_direction(manifest, &mut diagnostics);
+
+    let scope = validate_scope(manifest, base_url, &mut diagnostics);
+    validate_screenshots(manifest, &mut diagnostics);
+    self.validate_shortcuts(
+      manifest,
+      base_url,
+      scope.as_ref(),
+      &mut diagnostics,
+    );
+    validate_share_target(manifest, base_url, scope.as_ref(), &mut diagnostics);
+    validate_protocol_handlers(
+      manifest,
+      base_url,
+      scope.as_ref(),
+      &mut diagnostics,
+    );
+    validate_file_handlers(
+      manifest,
+      base_url,
+      scope.as_ref(),
+      &mut diagnostics,
+    );
+    validate_related(manifest, &mut diagnostics);
+
+    diagnostics
+  }
+}
+
+impl Validator {
+  /// Check `shortcuts` against `scope` and the platform limits. A `url` that
+  /// is not same-origin with and within `scope` is an error. More than
+  /// `shortcuts_max` shortcuts, and shortcuts without a 96x96 icon, are
+  /// warnings.
+  fn validate_shortcuts(
+    &self,
+    manifest: &Manifest,
+    base_url: &Url,
+    scope: Option<&Url>,
+    diagnostics: &mut Vec<Diagnostic>,
+  ) {
+    let shortcuts = manifest.get_shortcuts();
+    if shortcuts.len() > self.shortcuts_max {
+      let kind = DiagnosticKind::TooMany {
+        count: shortcuts.len(),
+        max: self.shortcuts_max,
+      };
+      diagnostics.push(Diagnostic::warning("shortcuts", kind));
+    }
+
+    for (index, shortcut) in shortcuts.iter().enumerate() {
+      validate_in_scope(
+        base_url,
+        scope,
+        &format!("shortcuts[{}].url", index),
+        shortcut.get_url(),
+        diagnostics,
+      );
+
+      let has_icon = shortcut.get_icons().iter().any(|icon| {
+        icon
+          .get_sizes()
+          .is_some_and(|sizes| sizes.contains(SHORTCUT_ICON))
+      });
+      if !has_icon {
+        let (width, height) = SHORTCUT_ICON;
+        let kind = DiagnosticKind::MissingIconSize { width, height };
+        let member = format!("shortcuts[{}].icons", index);
+        diagnostics.push(Diagnostic::warning(member, kind));
+      }
+    }
+  }
+}
+
+impl Default for Validator {
+  #[inline]
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<'a> Manifest<'a> {
+  /// Validate the manifest and fail if any diagnostic is an error.
+  ///
+  /// Returns the warnings if there are no errors. See `Validator::validate`
+  /// for the checks that are run.
+  ///
+  /// ## Example
+  /// ```rust
+  /// # extern crate webmanifest;
+  /// # use webmanifest::{Error, Manifest, Url};
+  /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+  /// let base_url = Url::parse("https://example.com/manifest.json")?;
+  /// let manifest = Manifest::builder("My Cool Application")
+  ///   .start_url("https://example.org/");
+  /// match manifest.check(&base_url) {
+  ///   Err(Error::Validation(diagnostics)) => assert_eq!(diagnostics.len(), 1),
+  ///   _ => panic!("expected a validation error"),
+  /// }
+  /// # Ok(())}
+  /// ```
+  #[inline]
+  pub fn check(&self, base_url: &Url) -> Result<Vec<Diagnostic>, Error> {
+    Validator::new().check(self, base_url)
+  }
+
+  /// Check the manifest for mistakes that make a user agent ignore or
+  /// truncate members. See `Validator::validate` for the checks that are run.
+  ///
+  /// ## Example
+  /// ```rust
+  /// # extern crate webmanifest;
+  /// # use webmanifest::{DiagnosticKind, Manifest, Url};
+  /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+  /// let base_url = Url::parse("https://example.com/manifest.json")?;
+  /// let manifest = Manifest::builder("My Cool Application")
+  ///   .start_url("/index.html")
+  ///   .scope("/app/");
+  /// let diagnostics = manifest.validate(&base_url);
+  /// match diagnostics[0].get_kind() {
+  ///   DiagnosticKind::OutOfScope { .. } => {}
+  ///   kind => panic!("unexpected diagnostic: {}", kind),
+  /// }
+  /// # Ok(())}
+  /// ```
+  #[inline]
+  pub fn validate(&self, base_url: &Url) -> Vec<Diagnostic> {
+    Validator::new().validate(self, base_url)
+  }
+}
+
+/// Check `start_url` and `scope`, returning the resolved scope if both could
+/// be resolved. Like user agents, a cross-origin `start_url` is replaced by
+/// `base_url`, and a `scope` that doesn't contain `start_url` is ignored in
+/// favor of the default scope.
+///
+/// A cross-origin `start_url`, or one outside `scope`, is an error. A `scope`
+/// with a query string or fragment is a warning, because neither takes part
+/// in scope matching.
+fn validate_scope(
+  manifest: &Manifest,
+  base_url: &Url,
+  diagnostics: &mut Vec<Diagnostic>,
+) -> Option<Url> {
+  let start_url = match manifest.get_start_url() {
+    Some(value) => match base_url.join(value) {
+      Ok(url) => url,
+      Err(_) => {
+        let kind = DiagnosticKind::InvalidUrl {
+          value: value.to_string(),
+        };
+        diagnostics.push(Diagnostic::error("start_url", kind));
+        return None;
+      }
+    },
+    None => base_url.clone(),
+  };
+
+  // A cross-origin `start_url` is ignored in favor of the document URL, so
+  // it's only reported once.
+  let cross_origin = start_url.origin() != base_url.origin();
+  let start_url = if cross_origin {
+    let kind = DiagnosticKind::CrossOrigin {
+      url: start_url.to_string(),
+      origin: base_url.origin().ascii_serialization(),
+    };
+    diagnostics.push(Diagnostic::error("start_url", kind));
+    base_url.clone()
+  } else {
+    start_url
+  };
+
+  let scope = match manifest.get_scope() {
+    Some(value) => match base_url.join(value) {
+      Ok(url) => url,
+      Err(_) => {
+        let kind = DiagnosticKind::InvalidUrl {
+          value: value.to_string(),
+        };
+        diagnostics.push(Diagnostic::error("scope", kind));
+        return None;
+      }
+    },
+    None => default_scope(&start_url),
+  };
+
+  if scope.query().is_some() || scope.fragment().is_some() {
+    let kind = DiagnosticKind::QueryOrFragment {
+      url: scope.to_string(),
+    };
+    diagnostics.push(Diagnostic::warning("scope", kind));
+  }
+
+  if !within_scope(&start_url, &scope) {
+    if !cross_origin {
+      let kind = DiagnosticKind::OutOfScope {
+        url: start_url.to_string(),
+        scope: scope.to_string(),
+      };
+      diagnostics.push(Diagnostic::error("start_url", kind));
+    }
+    return Some(default_scope(&start_url));
+  }
+
+  Some(scope)
+}
+
+/// Check `screenshots` against the rules for a richer install dialog, if
+/// there are any. Every declared size must be between 320 and 3840 pixels on
+/// each side, the longest side may be at most 2.3 times the shortest, and
+/// there must be at least one `wide` and one `narrow` screenshot. Everything
+/// is a warning, because the manifest still installs.
+fn validate_screenshots(
+  manifest: &Manifest,
+  diagnostics: &mut Vec<Diagnostic>,
+) {
+  let screenshots = manifest.get_screenshots();
+  if screenshots.is_empty() {
+    return;
+  }
+
+  for (index, screenshot) in screenshots.iter().enumerate() {
+    let member = format!("screenshots[{}].sizes", index);
+    let sizes = screenshot
+      .get_sizes()
+      .into_iter()
+      .flat_map(|sizes| sizes.iter());
+    for size in sizes {
+      let (width, height) = match *size {
+        Size::Exact { width, height } => (width, height),
+        Size::Any => continue,
+      };
+      let (short, long) = (width.min(height), width.max(height));
+      if short < SCREENSHOT_MIN || long > SCREENSHOT_MAX {
+        let kind = DiagnosticKind::DimensionsOutOfRange {
+          width,
+          height,
+          min: SCREENSHOT_MIN,
+          max: SCREENSHOT_MAX,
+        };
+        diagnostics.push(Diagnostic::warning(member.clone(), kind));
+      }
+      if f64::from(long) > f64::from(short) * SCREENSHOT_RATIO {
+        let kind = DiagnosticKind::AspectRatio {
+          width,
+          height,
+          max: SCREENSHOT_RATIO,
+        };
+        diagnostics.push(Diagnostic::warning(member.clone(), kind));
+      }
+    }
+  }
+
+  for &form_factor in &[FormFactor::Wide, FormFactor::Narrow] {
+    let found = screenshots
+      .iter()
+      .any(|screenshot| screenshot.get_form_factor() == Some(form_factor));
+    if !found {
+      let kind = DiagnosticKind::MissingFormFactor { form_factor };
+      diagnostics.push(Diagnostic::warning("screenshots", kind));
+    }
+  }
+}
+
+/// Check the `share_target` member. An `action` outside `scope`, a `GET`
+/// share target with `multipart/form-data` encoding, and files accepted by
+/// anything but a `multipart/form-data` `POST` are errors. File fields
+/// without `accept` are warnings.
+fn validate_share_target(
+  manifest: &Manifest,
+  base_url: &Url,
+  scope: Option<&Url>,
+  diagnostics: &mut Vec<Diagnostic>,
+) {
+  let share_target = match manifest.get_share_target() {
+    Some(share_target) => share_target,
+    None => return,
+  };
+  let member = "share_target.action";
+  validate_in_scope(
+    base_url,
+    scope,
+    member,
+    share_target.get_action(),
+    diagnostics,
+  );
+
+  let method = share_target.get_method();
+  let enctype = share_target.get_enctype();
+  if method == ShareMethod::Get && enctype != ShareEnctype::UrlEncoded {
+    let kind = DiagnosticKind::UnsupportedEnctype { method, enctype };
+    diagnostics.push(Diagnostic::error("share_target.enctype", kind));
+  }
+
+  let files = share_target.get_params().get_files();
+  let multipart =
+    method == ShareMethod::Post && enctype == ShareEnctype::Multipart;
+  if !files.is_empty() && !multipart {
+    let kind = DiagnosticKind::FilesRequireMultipart;
+    diagnostics.push(Diagnostic::error("share_target.params.files", kind));
+  }
+  for (index, file) in files.iter().enumerate() {
+    if file.get_accept().is_empty() {
+      let member = format!("share_target.params.files[{}].accept", index);
+      diagnostics
+        .push(Diagnostic::warning(member, DiagnosticKind::MissingMember));
+    }
+  }
+}
+
+/// Check the `protocol_handlers` member. A `protocol` that is neither
+/// safelisted nor starts with `web+`, a `url` without exactly one `%s`, and a
+/// `url` that is not same-origin with and within `scope` are errors.
+fn validate_protocol_handlers(
+  manifest: &Manifest,
+  base_url: &Url,
+  scope: Option<&Url>,
+  diagnostics: &mut Vec<Diagnostic>,
+) {
+  let handlers = manifest.get_protocol_handlers();
+  for (index, handler) in handlers.iter().enumerate() {
+    if !handler.has_valid_protocol() {
+      let kind = DiagnosticKind::UnsupportedProtocol {
+        protocol: handler.get_protocol().to_string(),
+      };
+      let member = format!("protocol_handlers[{}].protocol", index);
+      diagnostics.push(Diagnostic::error(member, kind));
+    }
+
+    let member = format!("protocol_handlers[{}].url", index);
+    let count = handler.get_url().matches("%s").count();
+    if count != 1 {
+      let kind = DiagnosticKind::Placeholder { count };
+      diagnostics.push(Diagnostic::error(member.clone(), kind));
+    }
+    validate_in_scope(base_url, scope, &member, handler.get_url(), diagnostics);
+  }
+}
+
+/// Check the `file_handlers` member. An `action` that is not same-origin
+/// with and within `scope`, a malformed MIME type, and an extension that
+/// doesn't start with `.` are errors. Handlers that accept nothing are
+/// warnings.
+fn validate_file_handlers(
+  manifest: &Manifest,
+  base_url: &Url,
+  scope: Option<&Url>,
+  diagnostics: &mut Vec<Diagnostic>,
+) {
+  let handlers = manifest.get_file_handlers();
+  for (index, handler) in handlers.iter().enumerate() {
+    let member = format!("file_handlers[{}].action", index);
+    validate_in_scope(
+      base_url,
+      scope,
+      &member,
+      handler.get_action(),
+      diagnostics,
+    );
+
+    let member = format!("file_handlers[{}].accept", index);
+    let mut accept = handler.get_accept().peekable();
+    if accept.peek().is_none() {
+      let kind = DiagnosticKind::MissingMember;
+      diagnostics.push(Diagnostic::warning(member.clone(), kind));
+    }
+    for (mime_type, extensions) in accept {
+      if !is_mime_type(mime_type) {
+        let kind = DiagnosticKind::InvalidMimeType {
+          value: mime_type.to_string(),
+        };
+        diagnostics.push(Diagnostic::error(member.clone(), kind));
+      }
+      for extension in extensions {
+        if !extension.starts_with('.') || extension.len() < 2 {
+          let kind = DiagnosticKind::InvalidExtension {
+            value: extension.to_string(),
+          };
+          diagnostics.push(Diagnostic::error(member.clone(), kind));
+        }
+      }
+    }
+  }
+}
+
+/// Check the `related_applications` and `prefer_related_applications`
+/// members. Entries on an unknown `platform` or with neither a `url` nor an
+/// `id` are warnings, and so is `prefer_related_applications` if none of the
+/// entries can be used.
+fn validate_related(manifest: &Manifest, diagnostics: &mut Vec<Diagnostic>) {
+  let related = manifest.get_related();
+  for (index, related) in related.iter().enumerate() {
+    if !related.has_known_platform() {
+      let kind = DiagnosticKind::UnknownPlatform {
+        platform: related.get_platform().to_string(),
+      };
+      let member = format!("related_applications[{}].platform", index);
+      diagnostics.push(Diagnostic::warning(member, kind));
+    }
+    if related.get_url().is_none() && related.get_id().is_none() {
+      let member = format!("related_applications[{}].url", index);
+      let kind = DiagnosticKind::MissingMember;
+      diagnostics.push(Diagnostic::warning(member, kind));
+    }
+  }
+
+  let prefer = manifest.get_prefer_related_applications() == Some(true);
+  if prefer && !related.iter().any(|related| related.is_usable()) {
+    let kind = DiagnosticKind::NoUsableRelated;
+    diagnostics.push(Diagnostic::warning("prefer_related_applications", kind));
+  }
+}
+
+/// Check that `lang` is well-formed, which is an error otherwise, and
+/// agrees with `dir`. A `dir` of `ltr` for a language written right-to-left,
+/// or of `rtl` for one written left-to-right, is a warning.
+fn validate_direction(manifest: &Manifest, diagnostics: &mut Vec<Diagnostic>) {
+  let lang = match manifest.get_lang() {
+    Some(lang) => lang,
+    None => return,
+  };
+  let tag = match LanguageTag::parse_kind(lang) {
+    Ok(tag) => tag,
+    Err(kind) => {
+      diagnostics.push(Diagnostic::error("lang", kind));
+      return;
+    }
+  };
+  let dir = manifest.get_direction();
+  if let Some(expected) = tag.direction() {
+    if dir != Direction::Auto && dir != expected {
+      let kind = DiagnosticKind::ConflictingDirection {
+        lang: lang.to_string(),
+        expected,
+      };
+      diagnostics.push(Diagnostic::warning("dir", kind));
+    }
+  }
+}
+
+/// Check if `value` is a MIME type of the form `type/subtype`, where both
+/// parts are HTTP tokens. `type/*` is allowed.
+fn is_mime_type(value: &str) -> bool {
+  let is_token = |part: &str| {
+    !part.is_empty()
+      && part
+        .bytes()
+        .all(|b| b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b))
+  };
+  match value.split_once('/') {
+    Some((kind, subtype)) => is_token(kind) && is_token(subtype),
+    None => false,
+  }
+}
+
+/// Check that the URL member `member` resolves, is same-origin with `scope`
+/// and lies within it.
+fn validate_in_scope(
+  base_url: &Url,
+  scope: Option<&Url>,
+  member: &str,
+  value: &str,
+  diagnostics: &mut Vec<Diagnostic>,
+) {
+  match base_url.join(value) {
+    Ok(url) => {
+      let scope = match scope {
+        Some(scope) => scope,
+        None => return,
+      };
+      if url.origin() != scope.origin() {
+        let kind = DiagnosticKind::CrossOrigin {
+          url: url.to_string(),
+          origin: scope.origin().ascii_serialization(),
+        };
+        diagnostics.push(Diagnostic::error(member, kind));
+      } else if !within_scope(&url, scope) {
+        let kind = DiagnosticKind::OutOfScope {
+          url: url.to_string(),
+          scope: scope.to_string(),
+        };
+        diagnostics.push(Diagnostic::error(member, kind));
+      }
+    }
+    Err(_) => {
+      let kind = DiagnosticKind::InvalidUrl {
+        value: value.to_string(),
+      };
+      diagnostics.push(Diagnostic::error(member, kind));
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::super::Shortcut;
+  use super::*;
+
+  #[test]
+  fn scope_without_start_url_is_ignored() {
+    let base_url = Url::parse("https://e.com/manifest.json").unwrap();
+    let manifest = Manifest::builder("App")
+      .start_url("/index.html")
+      .scope("/app/")
+      .shortcut(Shortcut::new("Home", "/home"));
+    let diagnostics = manifest.validate(&base_url);
+    let errors: Vec<_> = diagnostics
+      .iter()
+      .filter(|d| d.get_severity() == Severity::Error)
+      .map(|d| d.get_member())
+      .collect();
+    assert_eq!(errors, ["start_url"]);
+  }
+
+  #[test]
+  fn cross_origin_start_url_is_reported_once() {
+    let base_url = Url::parse("https://e.com/manifest.json").unwrap();
+    let manifest = Manifest::builder("App")
+      .start_url("https://example.org/")
+      .shortcut(Shortcut::new("Home", "/home"));
+    let errors: Vec<_> = manifest
+      .validate(&base_url)
+      .into_iter()
+      .filter(|d| d.get_severity() == Severity::Error)
+      .collect();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].get_member(), "start_url");
+    let kind = DiagnosticKind::CrossOrigin {
+      url: "https://example.org/".to_string(),
+      origin: "https://e.com".to_string(),
+    };
+    assert_eq!(errors[0].get_kind(), &kind);
+
+    let manifest = manifest.scope("/app/");
+    let errors: Vec<_> = manifest
+      .validate(&base_url)
+      .into_iter()
+      .filter(|d| d.get_severity() == Severity::Error)
+      .map(|d| d.get_member().to_string())
+      .collect();
+    assert_eq!(errors, ["start_url"]);
+  }
+
+  #[test]
+  fn shortcut_scope_matches_other_members() {
+    let base_url = Url::parse("https://e.com/manifest.json").unwrap();
+    let manifest = Manifest::builder("App")
+      .shortcut(Shortcut::new("Home", "https://other.com/"))
+      .shortcut(Shortcut::new("Admin", "/../admin"))
+      .shortcut(Shortcut::new("Broken", "https://["));
+    let diagnostics: Vec<_> = manifest
+      .validate(&base_url)
+      .into_iter()
+      .filter(|d| d.get_severity() == Severity::Error)
+      .collect();
+    assert_eq!(diagnostics.len(), 2);
+    assert_eq!(diagnostics[0].get_member(), "shortcuts[0].url");
+    let kind = DiagnosticKind::CrossOrigin {
+      url: "https://other.com/".to_string(),
+      origin: "https://e.com".to_string(),
+    };
+    assert_eq!(diagnostics[0].get_kind(), &kind);
+    assert_eq!(diagnostics[1].get_member(), "shortcuts[2].url");
+    let kind = DiagnosticKind::InvalidUrl {
+      value: "https://[".to_string(),
+    };
+    assert_eq!(diagnostics[1].get_kind(), &kind);
+  }
+}